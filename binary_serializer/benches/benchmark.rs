@@ -1,15 +1,50 @@
 use criterion::{BenchmarkId, black_box, Criterion, criterion_group, criterion_main};
 use binary_serializer::common::ByteEndian;
-use binary_serializer::decoder::FromBytes;
-use binary_serializer::encoder::ToBytes;
+use binary_serializer::decoder::{Deserializer, FromBytes};
+use binary_serializer::encoder::{Serializer, ToBytes};
+
+#[derive(Serializer, Deserializer)]
+enum Enum {
+  Unit,
+  Tuple(u32, u32),
+  Struct { x: u32, y: u32 },
+}
 
 fn criterion_benchmark(c: &mut Criterion) {
-  let bytes = vec![0u64; 16384].as_slice().to_bytes(ByteEndian::Little);
+  let swapped = match ByteEndian::Native.resolve() {
+    ByteEndian::Little => ByteEndian::Big,
+    _ => ByteEndian::Little,
+  };
+
+  // native endianness copies the whole slice at once, the other one goes element by element
+  for (name, endian) in [("from_bytes-native", ByteEndian::Native), ("from_bytes-swapped", swapped)] {
+    let bytes = vec![0u64; 16384].as_slice().to_bytes(endian);
+
+    c.bench_with_input(BenchmarkId::new(name, bytes.len()), &bytes, |b, bytes| b.iter(|| {
+      black_box(Vec::<u64>::from_bytes(bytes, endian).unwrap());
+    }));
+  }
+}
+
+fn enum_benchmark(c: &mut Criterion) {
+  let enums = (0..16384u32)
+    .map(|idx| match idx % 3 {
+      0 => Enum::Unit,
+      1 => Enum::Tuple(idx, idx),
+      _ => Enum::Struct { x: idx, y: idx },
+    })
+    .collect::<Vec<_>>();
+
+  let bytes = enums.to_bytes(ByteEndian::Little);
+
+  c.bench_with_input(BenchmarkId::new("enum-to_bytes", enums.len()), &enums, |b, enums| b.iter(|| {
+    black_box(enums.to_bytes(ByteEndian::Little));
+  }));
 
-  c.bench_with_input(BenchmarkId::new("from_bytes-v2", bytes.len()), &bytes, |b, bytes| b.iter(|| {
-    black_box(Vec::<u64>::from_bytes(bytes, ByteEndian::Little).unwrap());
+  c.bench_with_input(BenchmarkId::new("enum-from_bytes", bytes.len()), &bytes, |b, bytes| b.iter(|| {
+    black_box(Vec::<Enum>::from_bytes(bytes, ByteEndian::Little).unwrap());
   }));
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(benches, criterion_benchmark, enum_benchmark);
 criterion_main!(benches);
\ No newline at end of file
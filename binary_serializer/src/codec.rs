@@ -0,0 +1,119 @@
+use alloc::vec::Vec;
+
+use crate::common::{ByteEndian, EndianValue, read_varint, unzigzag, write_varint, zigzag};
+use crate::decoder::{DecoderError, DecoderResult, read_fixed};
+
+/// Integer types a [`Codec`] knows how to write.
+pub trait Integer<const SIZE: usize>: EndianValue<SIZE> + Copy {
+  const BITS: u32;
+  const SIGNED: bool;
+
+  /// Two's complement bits of the value, zero extended.
+  fn to_bits(self) -> u128;
+
+  /// Inverse of `to_bits`, `bits` has to fit in `BITS`.
+  fn from_bits(bits: u128) -> Self;
+}
+
+macro_rules! impl_integer {
+  ($(($type:ty, $unsigned:ty, $size:literal, $signed:literal)),+ $(,)?) => {
+    $(impl Integer<$size> for $type {
+      const BITS: u32 = $size * 8;
+      const SIGNED: bool = $signed;
+
+      fn to_bits(self) -> u128 {
+        self as $unsigned as u128
+      }
+
+      fn from_bits(bits: u128) -> Self {
+        bits as $unsigned as $type
+      }
+    })+
+  };
+}
+
+impl_integer!(
+  (u8, u8, 1, false), (u16, u16, 2, false), (u32, u32, 4, false), (u64, u64, 8, false), (u128, u128, 16, false), (usize, u64, 8, false),
+  (i8, u8, 1, true), (i16, u16, 2, true), (i32, u32, 4, true), (i64, u64, 8, true), (i128, u128, 16, true), (isize, u64, 8, true),
+);
+
+/// Decides how [`ByteEncoder`](crate::encoder::ByteEncoder) and [`ByteDecoder`](crate::decoder::ByteDecoder)
+/// lay out integers, floats are always written with their fixed width.
+pub trait Codec {
+  /// Whether integers keep their full width, which lets slices of them be copied in bulk.
+  const FIXED_WIDTH: bool = false;
+
+  fn write<T: Integer<SIZE>, const SIZE: usize>(bytes: &mut Vec<u8>, endian: ByteEndian, value: T);
+  fn read<T: Integer<SIZE>, const SIZE: usize>(bytes: &[u8], index: &mut usize, endian: ByteEndian) -> DecoderResult<T>;
+}
+
+/// Every integer takes its full width in the given endianness, the default.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FixedCodec;
+
+impl Codec for FixedCodec {
+  const FIXED_WIDTH: bool = true;
+
+  fn write<T: Integer<SIZE>, const SIZE: usize>(bytes: &mut Vec<u8>, endian: ByteEndian, value: T) {
+    bytes.extend_from_slice(&value.to_bytes_of(endian));
+  }
+
+  fn read<T: Integer<SIZE>, const SIZE: usize>(bytes: &[u8], index: &mut usize, endian: ByteEndian) -> DecoderResult<T> {
+    read_fixed(bytes, index, endian)
+  }
+}
+
+/// Every integer (including lengths) is a LEB128 varint, endianness doesn't apply.
+///
+/// Signed integers are varints of their two's complement bits, so small negative values are large.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct VarintCodec;
+
+impl Codec for VarintCodec {
+  fn write<T: Integer<SIZE>, const SIZE: usize>(bytes: &mut Vec<u8>, _endian: ByteEndian, value: T) {
+    write_varint(value.to_bits(), |byte| bytes.push(byte));
+  }
+
+  fn read<T: Integer<SIZE>, const SIZE: usize>(bytes: &[u8], index: &mut usize, _endian: ByteEndian) -> DecoderResult<T> {
+    read_varint(T::BITS, next_byte(bytes, index)).map(T::from_bits)
+  }
+}
+
+/// Like [`VarintCodec`], but signed integers are zigzag mapped first so small negative values stay small.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ZigZagCodec;
+
+impl Codec for ZigZagCodec {
+  fn write<T: Integer<SIZE>, const SIZE: usize>(bytes: &mut Vec<u8>, _endian: ByteEndian, value: T) {
+    let bits = match T::SIGNED {
+      true => zigzag(sign_extend::<T, SIZE>(value.to_bits())),
+      false => value.to_bits(),
+    };
+
+    write_varint(bits, |byte| bytes.push(byte));
+  }
+
+  fn read<T: Integer<SIZE>, const SIZE: usize>(bytes: &[u8], index: &mut usize, _endian: ByteEndian) -> DecoderResult<T> {
+    let bits = read_varint(T::BITS, next_byte(bytes, index))?;
+
+    Ok(match T::SIGNED {
+      true => T::from_bits(unzigzag(bits) as u128),
+      false => T::from_bits(bits),
+    })
+  }
+}
+
+fn sign_extend<T: Integer<SIZE>, const SIZE: usize>(bits: u128) -> i128 {
+  let shift = 128 - T::BITS;
+
+  ((bits << shift) as i128) >> shift
+}
+
+fn next_byte<'a>(bytes: &'a [u8], index: &'a mut usize) -> impl FnMut() -> DecoderResult<u8> + 'a {
+  move || {
+    let byte = *bytes.get(*index).ok_or_else(|| DecoderError::not_enough_bytes("varint", *index))?;
+    *index += 1;
+
+    Ok(byte)
+  }
+}
@@ -1,12 +1,20 @@
-use std::hash::Hash;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::{PhantomData, PhantomPinned};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
 
-use crate::decoder::{Decoder, DecoderResult, Deserializer};
+use crate::decoder::{Decoder, DecoderError, DecoderResult, Deserializer};
 use crate::encoder::{Encoder, Serializer};
 
 #[derive(Copy, Clone, Debug)]
 pub enum ByteEndian {
   Big,
   Little,
+  /// Whichever of `Big` or `Little` the target uses.
+  Native,
 }
 
 impl ByteEndian {
@@ -18,7 +26,16 @@ impl ByteEndian {
   pub const fn is_native(&self) -> bool {
     match self {
       ByteEndian::Big => cfg!(target_endian = "big"),
-      ByteEndian::Little => cfg!(target_endian = "little")
+      ByteEndian::Little => cfg!(target_endian = "little"),
+      ByteEndian::Native => true,
+    }
+  }
+
+  /// `Native` as the `Big` or `Little` it stands for, anything else as is.
+  pub const fn resolve(self) -> Self {
+    match self {
+      ByteEndian::Native => Self::NATIVE,
+      endian => endian,
     }
   }
 }
@@ -31,20 +48,104 @@ pub trait EndianValue<const SIZE: usize>: Sized {
   fn to_bytes_be(self) -> [u8; SIZE];
 
   fn from_bytes_of(endian: ByteEndian, bytes: [u8; SIZE]) -> Self {
-    match endian {
+    match endian.resolve() {
       ByteEndian::Big => Self::from_bytes_be(bytes),
-      ByteEndian::Little => Self::from_bytes_le(bytes)
+      _ => Self::from_bytes_le(bytes)
     }
   }
 
   fn to_bytes_of(self, endian: ByteEndian) -> [u8; SIZE] {
-    match endian {
+    match endian.resolve() {
       ByteEndian::Big => self.to_bytes_be(),
-      ByteEndian::Little => self.to_bytes_le(),
+      _ => self.to_bytes_le(),
     }
   }
 }
 
+/// Types that always encode to exactly `WIRE_SIZE` bytes, regardless of their value.
+pub trait FixedSize {
+  const WIRE_SIZE: usize;
+}
+
+impl FixedSize for bool {
+  const WIRE_SIZE: usize = 1;
+}
+
+impl FixedSize for char {
+  const WIRE_SIZE: usize = 4;
+}
+
+#[cfg(feature = "std")]
+impl FixedSize for Ipv4Addr {
+  const WIRE_SIZE: usize = 4;
+}
+
+#[cfg(feature = "std")]
+impl FixedSize for Ipv6Addr {
+  const WIRE_SIZE: usize = 16;
+}
+
+#[cfg(feature = "std")]
+impl FixedSize for SocketAddrV4 {
+  const WIRE_SIZE: usize = 4 + 2;
+}
+
+#[cfg(feature = "std")]
+impl FixedSize for SocketAddrV6 {
+  const WIRE_SIZE: usize = 16 + 2 + 4 + 4;
+}
+
+impl<T: ?Sized> FixedSize for PhantomData<T> {
+  const WIRE_SIZE: usize = 0;
+}
+
+impl FixedSize for PhantomPinned {
+  const WIRE_SIZE: usize = 0;
+}
+
+/// Whole seconds as `u64` followed by the sub-second nanos as `u32`.
+impl FixedSize for Duration {
+  const WIRE_SIZE: usize = 12;
+}
+
+impl<T: FixedSize> FixedSize for Range<T> {
+  const WIRE_SIZE: usize = 2 * T::WIRE_SIZE;
+}
+
+impl<T: FixedSize> FixedSize for RangeInclusive<T> {
+  const WIRE_SIZE: usize = 2 * T::WIRE_SIZE;
+}
+
+impl<T: FixedSize, const N: usize> FixedSize for [T; N] {
+  const WIRE_SIZE: usize = T::WIRE_SIZE * N;
+}
+
+macro_rules! impl_fixed_size_tuple {
+  ($($name:ident),*) => {
+    impl <$($name: FixedSize),*> FixedSize for ($($name,)*) {
+      const WIRE_SIZE: usize = 0 $(+ $name::WIRE_SIZE)*;
+    }
+  };
+}
+
+impl_fixed_size_tuple!();
+impl_fixed_size_tuple!(A);
+impl_fixed_size_tuple!(A, B);
+impl_fixed_size_tuple!(A, B, C);
+impl_fixed_size_tuple!(A, B, C, D);
+impl_fixed_size_tuple!(A, B, C, D, E);
+impl_fixed_size_tuple!(A, B, C, D, E, F);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K, L);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K, L, M);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K, L, M, N);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q);
+impl_fixed_size_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q, R);
+
 macro_rules! impl_from_endian {
   ($(($type:ty, $size:literal)),+ $(,)?) => {
     $(impl EndianValue<$size> for $type {
@@ -63,6 +164,10 @@ macro_rules! impl_from_endian {
       fn to_bytes_be(self) -> [u8; $size] {
         self.to_be_bytes()
       }
+    }
+
+    impl FixedSize for $type {
+      const WIRE_SIZE: usize = $size;
     })+
   };
 }
@@ -73,17 +178,109 @@ impl_from_endian!(
   (f32, 4), (f64, 8)
 );
 
-pub struct MapEntry<K: Eq + Hash, V>(pub K, pub V);
+#[cfg(feature = "half")]
+impl_from_endian!((half::f16, 2));
 
-impl<K: Serializer + Eq + Hash, V: Serializer> Serializer for MapEntry<&K, &V> {
+/// Writes `value` as an unsigned LEB128 varint, 7 bits per byte with the high bit marking continuation.
+pub(crate) fn write_varint(mut value: u128, mut push: impl FnMut(u8)) {
+  while value >= 0x80 {
+    push(value as u8 | 0x80);
+    value >>= 7;
+  }
+
+  push(value as u8);
+}
+
+/// Reads an unsigned LEB128 varint that has to fit in `bits` bits,
+/// rejecting overlong encodings (a trailing zero byte) and values that overflow.
+pub(crate) fn read_varint(bits: u32, mut next: impl FnMut() -> DecoderResult<u8>) -> DecoderResult<u128> {
+  let mut value = 0u128;
+  let mut shift = 0;
+
+  loop {
+    let byte = next()?;
+    let low = u128::from(byte & 0x7f);
+
+    if shift >= bits || (bits - shift < 7 && low >> (bits - shift) != 0) {
+      return Err(DecoderError::invalid_varint(bits));
+    }
+
+    value |= low << shift;
+
+    if byte & 0x80 == 0 {
+      if byte == 0 && shift > 0 {
+        return Err(DecoderError::invalid_varint(bits));
+      }
+
+      return Ok(value);
+    }
+
+    shift += 7;
+  }
+}
+
+/// Maps signed to unsigned so values near zero stay small, `0, -1, 1, -2, ...` become `0, 1, 2, 3, ...`.
+///
+/// Narrower integers sign extended to `i128` map to the same value as they would at their own width.
+pub(crate) fn zigzag(value: i128) -> u128 {
+  ((value << 1) ^ (value >> 127)) as u128
+}
+
+pub(crate) fn unzigzag(value: u128) -> i128 {
+  (value >> 1) as i128 ^ -((value & 1) as i128)
+}
+
+pub struct MapEntry<K, V>(pub K, pub V);
+
+impl<K: Serializer, V: Serializer> Serializer for MapEntry<&K, &V> {
   fn encode(&self, encoder: &mut impl Encoder) {
     self.0.encode(encoder);
     self.1.encode(encoder);
   }
 }
 
-impl<K: Deserializer + Eq + Hash, V: Deserializer> Deserializer for MapEntry<K, V> {
+impl<K: Deserializer, V: Deserializer> Deserializer for MapEntry<K, V> {
   fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
     Ok(MapEntry(decoder.decode_value::<K>()?, decoder.decode_value::<V>()?))
   }
+}
+
+/// Field types `#[binary(fixed = N)]` works with, written as exactly `N` bytes with no length prefix.
+pub trait FixedBytes: Sized {
+  /// At most `len` bytes of the value, [`Encoder::encode_fixed_bytes`] pads the rest with zeros.
+  fn fixed_bytes(&self, len: usize) -> &[u8];
+
+  /// `start` is where `bytes` begin in the input, errors point at the whole input rather than the field.
+  fn from_fixed_bytes(bytes: Vec<u8>, start: usize) -> DecoderResult<Self>;
+}
+
+impl FixedBytes for Vec<u8> {
+  fn fixed_bytes(&self, len: usize) -> &[u8] {
+    &self[..self.len().min(len)]
+  }
+
+  fn from_fixed_bytes(bytes: Vec<u8>, _start: usize) -> DecoderResult<Self> {
+    Ok(bytes)
+  }
+}
+
+/// Always UTF-8, even with the `utf16_strings` feature, and padding zeros are trimmed when decoded.
+impl FixedBytes for String {
+  fn fixed_bytes(&self, len: usize) -> &[u8] {
+    let mut end = self.len().min(len);
+
+    // never cut a character in half, it would no longer decode
+    while !self.is_char_boundary(end) {
+      end -= 1;
+    }
+
+    &self.as_bytes()[..end]
+  }
+
+  fn from_fixed_bytes(mut bytes: Vec<u8>, start: usize) -> DecoderResult<Self> {
+    let end = bytes.iter().rposition(|&byte| byte != 0).map_or(0, |idx| idx + 1);
+    bytes.truncate(end);
+
+    String::from_utf8(bytes).map_err(|err| DecoderError::invalid_utf8(start + err.utf8_error().valid_up_to()))
+  }
 }
\ No newline at end of file
@@ -0,0 +1,99 @@
+//! Deflate compressed values, worth it for large or repetitive data that's stored rather than sent around.
+
+use std::io::{Read, Write};
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+
+use crate::common::ByteEndian;
+use crate::decoder::{ByteDecoder, DecoderError, DecoderResult, Deserializer};
+use crate::encoder::{ByteEncoder, Encoder, EncoderResult, Serializer};
+
+/// Wraps a [`ByteEncoder`], [`CompressedEncoder::finish`] compresses everything encoded through it.
+pub struct CompressedEncoder {
+  encoder: ByteEncoder,
+  level: u32,
+}
+
+impl CompressedEncoder {
+  /// `level` goes from `0` (stored as is) to `9` (smallest but slowest), higher is treated as `9`.
+  pub fn new(endian: ByteEndian, level: u32) -> Self {
+    Self { encoder: ByteEncoder::new(endian), level: level.min(9) }
+  }
+
+  pub fn encoder(&mut self) -> &mut ByteEncoder {
+    &mut self.encoder
+  }
+
+  pub fn encode_value<T: Serializer + ?Sized>(&mut self, value: &T) {
+    value.encode(&mut self.encoder);
+  }
+
+  pub fn finish(self) -> EncoderResult<Vec<u8>> {
+    let bytes = self.encoder.finish()?;
+    let mut compressor = DeflateEncoder::new(Vec::with_capacity(bytes.len() / 2), Compression::new(self.level));
+
+    // writing into a `Vec` can't fail
+    compressor.write_all(&bytes).unwrap();
+    Ok(compressor.finish().unwrap())
+  }
+}
+
+/// Limit of [`CompressedDecoder::new`] on the decompressed size.
+pub const DEFAULT_MAX_DECOMPRESSED: usize = 64 * 1024 * 1024;
+
+/// Decompresses up front, values are then decoded from the result through [`CompressedDecoder::decoder`].
+pub struct CompressedDecoder {
+  bytes: Vec<u8>,
+  endian: ByteEndian,
+}
+
+impl CompressedDecoder {
+  /// Fails with [`DecoderError::Decompression`] if `bytes` aren't valid deflate data,
+  /// see [`CompressedDecoder::with_limit`] for the size they may decompress to.
+  pub fn new(bytes: &[u8], endian: ByteEndian) -> DecoderResult<Self> {
+    Self::with_limit(bytes, endian, DEFAULT_MAX_DECOMPRESSED)
+  }
+
+  /// Fails with [`DecoderError::DecompressedTooLarge`] as soon as `bytes` decompress to more than `limit` bytes,
+  /// a few bytes of input can otherwise expand to gigabytes.
+  pub fn with_limit(bytes: &[u8], endian: ByteEndian, limit: usize) -> DecoderResult<Self> {
+    let mut decompressed = Vec::new();
+
+    DeflateDecoder::new(bytes)
+      .take((limit as u64).saturating_add(1))
+      .read_to_end(&mut decompressed)
+      .map_err(DecoderError::decompression)?;
+
+    if decompressed.len() > limit {
+      return Err(DecoderError::decompressed_too_large(limit));
+    }
+
+    Ok(Self { bytes: decompressed, endian })
+  }
+
+  pub fn decoder(&self) -> ByteDecoder<'_> {
+    ByteDecoder::new(&self.bytes, self.endian)
+  }
+}
+
+/// Like [`ToBytes::try_to_bytes`](crate::encoder::ToBytes::try_to_bytes), compressed at `level`, see [`CompressedEncoder::new`].
+pub fn to_bytes_compressed<T: Serializer + ?Sized>(value: &T, endian: ByteEndian, level: u32) -> EncoderResult<Vec<u8>> {
+  let mut encoder = CompressedEncoder::new(endian, level);
+  encoder.encode_value(value);
+
+  encoder.finish()
+}
+
+/// Like [`FromBytes::from_bytes_exact`](crate::decoder::FromBytes::from_bytes_exact) on the decompressed `bytes`.
+pub fn from_bytes_compressed<T: Deserializer>(bytes: &[u8], endian: ByteEndian) -> DecoderResult<T> {
+  let decompressed = CompressedDecoder::new(bytes, endian)?;
+  let mut decoder = decompressed.decoder();
+  let value = T::decode(&mut decoder)?;
+
+  match decoder.remaining() {
+    0 => Ok(value),
+    remaining => Err(DecoderError::trailing_bytes(remaining)),
+  }
+}
\ No newline at end of file
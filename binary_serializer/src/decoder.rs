@@ -1,16 +1,46 @@
-use std::any::type_name;
-use std::collections::HashMap;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use alloc::format;
+use alloc::rc::{self, Rc};
+use alloc::string::{String, ToString};
+use alloc::sync::{self, Arc};
+use alloc::vec::Vec;
+use core::any::type_name;
+use core::fmt::{Display, Formatter};
+use core::hash::{BuildHasher, Hash};
+use core::iter::FusedIterator;
+use core::marker::{PhantomData, PhantomPinned};
+use core::mem::size_of;
+use core::num::{
+  NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+  NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+};
+use core::ops::{Range, RangeInclusive};
+use core::sync::atomic::{
+  AtomicBool, AtomicI8, AtomicI16, AtomicI32, AtomicI64, AtomicIsize,
+  AtomicU8, AtomicU16, AtomicU32, AtomicU64, AtomicUsize,
+};
+use core::time::Duration;
+#[cfg(feature = "half")]
+use half::f16;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt::{Display, Formatter};
-use std::hash::Hash;
-use std::panic::catch_unwind;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead, BufReader, Read};
+#[cfg(feature = "std")]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 
 #[cfg(feature = "binary_serializer_derive")]
-pub use binary_serializer_derive::Deserializer;
+pub use binary_serializer_derive::{Deserializer, DeserializerBorrowed};
 
-use crate::common::{ByteEndian, EndianValue, MapEntry};
+use crate::codec::{Codec, FixedCodec, Integer};
+use crate::common::{ByteEndian, EndianValue, FixedSize, MapEntry, read_varint, unzigzag};
+use crate::encoder::{Serializer, ToBytes};
 
-pub type DecoderResult<T> = std::result::Result<T, DecoderError>;
+pub type DecoderResult<T> = core::result::Result<T, DecoderError>;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum DecoderError {
@@ -23,9 +53,87 @@ pub enum DecoderError {
     type_name: String,
     index: usize,
   },
+  InvalidUTF8 {
+    index: usize
+  },
+  #[cfg(feature = "std")]
+  Io {
+    kind: io::ErrorKind,
+    message: String,
+  },
   InvalidUTF16 {
     index: usize
   },
+  LengthTooLarge {
+    requested: usize,
+    limit: usize,
+  },
+  UnknownOpcode {
+    opcode: u16,
+  },
+  UnknownVersion {
+    version: u32,
+  },
+  InvalidDuration {
+    nanos: u32,
+  },
+  InvalidVarint {
+    bits: u32,
+  },
+  IndexOutOfBounds {
+    index: usize,
+    len: usize,
+  },
+  InvalidVariant {
+    type_name: String,
+    index: u64,
+    count: usize,
+  },
+  InvalidVariantName {
+    type_name: String,
+    name: String,
+  },
+  NonCanonical {
+    index: usize,
+  },
+  InvalidChar {
+    value: u32,
+  },
+  UnexpectedZero {
+    type_name: String,
+  },
+  TrailingBytes {
+    remaining: usize,
+  },
+  RecursionLimitExceeded {
+    limit: usize,
+  },
+  InvalidBool {
+    value: u8,
+  },
+  NonFiniteFloat {
+    type_name: String,
+  },
+  Unsupported {
+    operation: String,
+  },
+  MissingField {
+    type_name: String,
+    field: String,
+  },
+  #[cfg(feature = "checksum")]
+  ChecksumMismatch {
+    expected: u32,
+    actual: u32,
+  },
+  #[cfg(feature = "compress")]
+  Decompression {
+    message: String,
+  },
+  #[cfg(feature = "compress")]
+  DecompressedTooLarge {
+    limit: usize,
+  },
 }
 
 impl DecoderError {
@@ -37,10 +145,109 @@ impl DecoderError {
     Self::NotEnoughMemorySlice { len, index }
   }
 
+  #[cfg(feature = "std")]
+  pub fn io(err: io::Error) -> Self {
+    Self::Io { kind: err.kind(), message: err.to_string() }
+  }
+
+  pub fn invalid_utf8(index: usize) -> Self {
+    Self::InvalidUTF8 { index }
+  }
+
   pub fn invalid_utf16(index: usize) -> Self {
     Self::InvalidUTF16 { index }
   }
 
+  pub fn length_too_large(requested: usize, limit: usize) -> Self {
+    Self::LengthTooLarge { requested, limit }
+  }
+
+  pub fn unknown_opcode(opcode: u16) -> Self {
+    Self::UnknownOpcode { opcode }
+  }
+
+  pub fn unknown_version(version: u32) -> Self {
+    Self::UnknownVersion { version }
+  }
+
+  pub fn invalid_duration(nanos: u32) -> Self {
+    Self::InvalidDuration { nanos }
+  }
+
+  pub fn invalid_varint(bits: u32) -> Self {
+    Self::InvalidVarint { bits }
+  }
+
+  pub fn index_out_of_bounds(index: usize, len: usize) -> Self {
+    Self::IndexOutOfBounds { index, len }
+  }
+
+  pub fn invalid_variant(type_name: impl ToString, index: u64, count: usize) -> Self {
+    Self::InvalidVariant {
+      type_name: type_name.to_string(),
+      index,
+      count,
+    }
+  }
+
+  pub fn invalid_variant_name(type_name: impl ToString, name: impl ToString) -> Self {
+    Self::InvalidVariantName {
+      type_name: type_name.to_string(),
+      name: name.to_string(),
+    }
+  }
+
+  pub fn non_canonical(index: usize) -> Self {
+    Self::NonCanonical { index }
+  }
+
+  pub fn invalid_char(value: u32) -> Self {
+    Self::InvalidChar { value }
+  }
+
+  pub fn unexpected_zero(type_name: impl ToString) -> Self {
+    Self::UnexpectedZero { type_name: type_name.to_string() }
+  }
+
+  pub fn trailing_bytes(remaining: usize) -> Self {
+    Self::TrailingBytes { remaining }
+  }
+
+  pub fn recursion_limit_exceeded(limit: usize) -> Self {
+    Self::RecursionLimitExceeded { limit }
+  }
+
+  pub fn invalid_bool(value: u8) -> Self {
+    Self::InvalidBool { value }
+  }
+
+  pub fn non_finite_float(type_name: impl ToString) -> Self {
+    Self::NonFiniteFloat { type_name: type_name.to_string() }
+  }
+
+  pub fn unsupported(operation: impl ToString) -> Self {
+    Self::Unsupported { operation: operation.to_string() }
+  }
+
+  pub fn missing_field(type_name: impl ToString, field: impl ToString) -> Self {
+    Self::MissingField { type_name: type_name.to_string(), field: field.to_string() }
+  }
+
+  #[cfg(feature = "checksum")]
+  pub fn checksum_mismatch(expected: u32, actual: u32) -> Self {
+    Self::ChecksumMismatch { expected, actual }
+  }
+
+  #[cfg(feature = "compress")]
+  pub fn decompression(err: io::Error) -> Self {
+    Self::Decompression { message: err.to_string() }
+  }
+
+  #[cfg(feature = "compress")]
+  pub fn decompressed_too_large(limit: usize) -> Self {
+    Self::DecompressedTooLarge { limit }
+  }
+
   pub fn not_enough_bytes(type_name: impl ToString, index: usize) -> Self {
     Self::NotEnoughBytes {
       type_name: type_name.to_string(),
@@ -50,7 +257,7 @@ impl DecoderError {
 }
 
 impl Display for DecoderError {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     match self {
       DecoderError::Custom(msg) => {
         f.write_str(msg)
@@ -58,19 +265,135 @@ impl Display for DecoderError {
       DecoderError::NotEnoughBytes { type_name, index } => {
         write!(f, "not enough bytes left to decode `{}` starting at index `{}`", type_name, index)
       }
+      #[cfg(feature = "std")]
+      DecoderError::Io { message, .. } => {
+        write!(f, "failed to read input: {}", message)
+      }
+      DecoderError::InvalidUTF8 { index } => {
+        write!(f, "string was encoded with invalid UTF8 at index `{}`", index)
+      }
       DecoderError::InvalidUTF16 { index } => {
         write!(f, "string was encoded with invalid UTF16 starting at index `{}`", index)
       }
       DecoderError::NotEnoughMemorySlice { len: size, index } => {
         write!(f, "not enough memory to allocate slice with length of `{}` starting at index `{}`", size, index)
       }
+      DecoderError::LengthTooLarge { requested, limit } => {
+        write!(f, "slice length of `{}` exceeds the limit of `{}`", requested, limit)
+      }
+      DecoderError::UnknownOpcode { opcode } => {
+        write!(f, "no rpc method registered for opcode `{}`", opcode)
+      }
+      DecoderError::UnknownVersion { version } => {
+        write!(f, "no migration registered from version `{}`", version)
+      }
+      DecoderError::InvalidDuration { nanos } => {
+        write!(f, "duration has non-canonical sub-second nanos `{}`", nanos)
+      }
+      DecoderError::InvalidVarint { bits } => {
+        write!(f, "varint is overlong or doesn't fit in `{}` bits", bits)
+      }
+      DecoderError::IndexOutOfBounds { index, len } => {
+        write!(f, "index `{}` is out of bounds for slice with length of `{}`", index, len)
+      }
+      DecoderError::InvalidVariant { type_name, index, count } => {
+        write!(f, "invalid variant `{}` for `{}` which has `{}` variants", index, type_name, count)
+      }
+      DecoderError::InvalidVariantName { type_name, name } => {
+        write!(f, "`{}` has no variant named `{}`", type_name, name)
+      }
+      DecoderError::NonCanonical { index } => {
+        write!(f, "input is not canonically encoded, re-encoding differs starting at index `{}`", index)
+      }
+      DecoderError::InvalidChar { value } => {
+        write!(f, "`{:#x}` is not a valid unicode scalar value", value)
+      }
+      DecoderError::UnexpectedZero { type_name } => {
+        write!(f, "decoded zero for `{}`", type_name)
+      }
+      DecoderError::TrailingBytes { remaining } => {
+        write!(f, "`{}` bytes left over after decoding", remaining)
+      }
+      DecoderError::RecursionLimitExceeded { limit } => {
+        write!(f, "values are nested deeper than the limit of `{}`", limit)
+      }
+      DecoderError::InvalidBool { value } => {
+        write!(f, "`{}` is not a valid bool, expected `0` or `1`", value)
+      }
+      DecoderError::NonFiniteFloat { type_name } => {
+        write!(f, "decoded `{}` is NaN or infinite", type_name)
+      }
+      DecoderError::Unsupported { operation } => {
+        write!(f, "decoder doesn't support {}", operation)
+      }
+      DecoderError::MissingField { type_name, field } => {
+        write!(f, "`{}` was written without field `{}`, which has no default", type_name, field)
+      }
+      #[cfg(feature = "checksum")]
+      DecoderError::ChecksumMismatch { expected, actual } => {
+        write!(f, "checksum `{:#010x}` doesn't match `{:#010x}` of the data", expected, actual)
+      }
+      #[cfg(feature = "compress")]
+      DecoderError::Decompression { message } => {
+        write!(f, "failed to decompress input: {}", message)
+      }
+      #[cfg(feature = "compress")]
+      DecoderError::DecompressedTooLarge { limit } => {
+        write!(f, "decompressed input is larger than the limit of `{}` bytes", limit)
+      }
     }
   }
 }
 
+// `core::error::Error` needs a newer compiler than the rest of the crate
+#[cfg(feature = "std")]
 impl Error for DecoderError {}
 
 pub trait Decoder: Sized {
+  /// Endianness primitives are currently read in.
+  ///
+  /// Only decoders that override it have one to report, others return [`ByteEndian::Native`] and ignore [`Decoder::set_endian`].
+  fn endian(&self) -> ByteEndian {
+    ByteEndian::Native
+  }
+
+  /// Changes the endianness of the primitives that follow, see [`Decoder::with_endian`].
+  fn set_endian(&mut self, endian: ByteEndian) {
+    let _ = endian;
+  }
+
+  /// Runs `f` with `endian` in place of the current endianness, for values that differ from their surroundings.
+  fn with_endian<T>(&mut self, endian: ByteEndian, f: impl FnOnce(&mut Self) -> T) -> T {
+    let previous = self.endian();
+    self.set_endian(endian);
+
+    let value = f(self);
+    self.set_endian(previous);
+
+    value
+  }
+
+  /// Whether the input is used up, `#[binary(default)]` fields missing from the end of older data rely on it.
+  ///
+  /// Decoders that can't tell always return `false`.
+  fn at_end(&mut self) -> bool { false }
+
+  /// Amount of bytes decoded so far, which error indices are relative to.
+  ///
+  /// Decoders that don't keep count always return `0`.
+  fn position(&self) -> usize { 0 }
+
+  /// Decodes a block written by [`Encoder::encode_block`] with `f`, what `f` leaves of it is skipped.
+  ///
+  /// `f` runs on this decoder so its settings and nesting depth carry over, reading past the block fails.
+  /// Decoders that can't bound their input fail with [`DecoderError::Unsupported`].
+  ///
+  /// [`Encoder::encode_block`]: crate::encoder::Encoder::encode_block
+  fn decode_block<T>(&mut self, f: impl FnOnce(&mut Self) -> DecoderResult<T>) -> DecoderResult<T> {
+    let _ = f;
+    Err(DecoderError::unsupported("length prefixed blocks"))
+  }
+
   fn decode_u8(&mut self) -> DecoderResult<u8>;
   fn decode_u16(&mut self) -> DecoderResult<u16>;
   fn decode_u32(&mut self) -> DecoderResult<u32>;
@@ -88,127 +411,1114 @@ pub trait Decoder: Sized {
   fn decode_f32(&mut self) -> DecoderResult<f32>;
   fn decode_f64(&mut self) -> DecoderResult<f64>;
 
+  #[cfg(feature = "half")]
+  fn decode_f16(&mut self) -> DecoderResult<f16> { self.decode_u16().map(f16::from_bits) }
+
   fn decode_bool(&mut self) -> DecoderResult<bool> { self.decode_u8().map(|it| it != 0) }
 
+  fn decode_varint_u64(&mut self) -> DecoderResult<u64> {
+    read_varint(64, || self.decode_u8()).map(|it| it as u64)
+  }
+
+  fn decode_varint_i64(&mut self) -> DecoderResult<i64> {
+    self.decode_varint_u64().map(|it| unzigzag(it.into()) as i64)
+  }
+
+  fn decode_i8_zigzag(&mut self) -> DecoderResult<i8> { read_varint(8, || self.decode_u8()).map(|it| unzigzag(it) as i8) }
+  fn decode_i16_zigzag(&mut self) -> DecoderResult<i16> { read_varint(16, || self.decode_u8()).map(|it| unzigzag(it) as i16) }
+  fn decode_i32_zigzag(&mut self) -> DecoderResult<i32> { read_varint(32, || self.decode_u8()).map(|it| unzigzag(it) as i32) }
+  fn decode_i64_zigzag(&mut self) -> DecoderResult<i64> { read_varint(64, || self.decode_u8()).map(|it| unzigzag(it) as i64) }
+  fn decode_i128_zigzag(&mut self) -> DecoderResult<i128> { read_varint(128, || self.decode_u8()).map(unzigzag) }
+  fn decode_isize_zigzag(&mut self) -> DecoderResult<isize> { self.decode_i64_zigzag().map(|it| it as isize) }
+
+  fn decode_sorted_ints(&mut self) -> DecoderResult<Vec<u64>> {
+    let len = self.decode_varint_u64()? as usize;
+
+    // nothing bounds `len` here, the vec only grows as values actually arrive
+    read_sorted_ints(self, len, Vec::new())
+  }
+
   fn decode_slice<T: Deserializer>(&mut self) -> DecoderResult<Vec<T>>;
+
+  /// Reads the length of a serialized `Vec<T>`, its elements are then decoded one at a time
+  /// as the returned [`SliceDecoder`] is iterated instead of all being collected up front.
+  fn decode_slice_iter<T: Deserializer>(&mut self) -> DecoderResult<SliceDecoder<'_, Self, T>> {
+    let remaining = self.decode_usize()?;
+
+    Ok(SliceDecoder { decoder: self, remaining, _marker: PhantomData })
+  }
+
   fn decode_string(&mut self) -> DecoderResult<String>;
-  fn decode_map<K: Deserializer + Eq + Hash, V: Deserializer>(&mut self) -> DecoderResult<HashMap<K, V>>;
-  fn decode_value<T: Deserializer>(&mut self) -> DecoderResult<T>;
+
+  /// Counterpart of [`Encoder::encode_bytes`], see [`ByteDecoder::decode_bytes`] to borrow them instead.
+  fn decode_byte_vec(&mut self) -> DecoderResult<Vec<u8>> {
+    self.decode_slice()
+  }
+
+  /// Inverse of [`Encoder::encode_fixed_bytes`], exactly `len` bytes with no length prefix.
+  ///
+  /// [`Encoder::encode_fixed_bytes`]: crate::encoder::Encoder::encode_fixed_bytes
+  fn decode_fixed_bytes(&mut self, len: usize) -> DecoderResult<Vec<u8>> {
+    (0..len).map(|_| self.decode_u8()).collect()
+  }
+
+  #[cfg(feature = "std")]
+  fn decode_map<K: Deserializer + Eq + Hash, V: Deserializer, S: BuildHasher + Default>(&mut self) -> DecoderResult<HashMap<K, V, S>> {
+    let entries = self.decode_slice::<MapEntry<K, V>>()?;
+    let mut map = HashMap::with_capacity_and_hasher(entries.len(), S::default());
+
+    for entry in entries {
+      map.insert(entry.0, entry.1);
+    }
+
+    Ok(map)
+  }
+
+  fn decode_value<T: Deserializer>(&mut self) -> DecoderResult<T> {
+    T::decode(self)
+  }
+
+  /// Inverse of [`Encoder::encode_sentinel_option`], a value equal to `none` decodes as `None`.
+  ///
+  /// [`Encoder::encode_sentinel_option`]: crate::encoder::Encoder::encode_sentinel_option
+  fn decode_sentinel_option<T: Deserializer + PartialEq>(&mut self, none: T) -> DecoderResult<Option<T>> {
+    let value = self.decode_value::<T>()?;
+
+    Ok((value != none).then_some(value))
+  }
+
+  fn decode_enum_tag<E: EnumDeserializer>(&mut self) -> DecoderResult<E::Tag> {
+    E::decode_tag(self)
+  }
+
+  fn decode_enum_payload<E: EnumDeserializer>(&mut self, tag: E::Tag) -> DecoderResult<E> {
+    E::decode_payload(tag, self)
+  }
+}
+
+/// The values of [`Decoder::decode_sorted_ints`] that follow their count, appended to `vec`.
+fn read_sorted_ints(decoder: &mut impl Decoder, len: usize, mut vec: Vec<u64>) -> DecoderResult<Vec<u64>> {
+  let mut prev = 0u64;
+
+  for idx in 0..len {
+    let value = decoder.decode_varint_u64()?;
+
+    prev = if idx == 0 {
+      value
+    } else {
+      prev.wrapping_add(unzigzag(value.into()) as u64)
+    };
+
+    vec.push(prev);
+  }
+
+  Ok(vec)
+}
+
+/// Lazy iterator over the elements of a serialized `Vec<T>`, see [`Decoder::decode_slice_iter`].
+///
+/// Stops after the first error, the decoder is left somewhere in the middle of the sequence.
+pub struct SliceDecoder<'a, D: Decoder, T> {
+  decoder: &'a mut D,
+  remaining: usize,
+  _marker: PhantomData<fn() -> T>,
 }
 
-pub struct ByteDecoder<'a> {
+impl<D: Decoder, T> SliceDecoder<'_, D, T> {
+  /// Elements not decoded yet.
+  pub fn remaining(&self) -> usize { self.remaining }
+}
+
+impl<D: Decoder, T: Deserializer> Iterator for SliceDecoder<'_, D, T> {
+  type Item = DecoderResult<T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.remaining == 0 {
+      return None;
+    }
+
+    let value = self.decoder.decode_value::<T>();
+    self.remaining = if value.is_ok() { self.remaining - 1 } else { 0 };
+
+    Some(value)
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (0, Some(self.remaining))
+  }
+}
+
+impl<D: Decoder, T: Deserializer> FusedIterator for SliceDecoder<'_, D, T> {}
+
+pub(crate) fn read_fixed<T: EndianValue<SIZE>, const SIZE: usize>(bytes: &[u8], index: &mut usize, endian: ByteEndian) -> DecoderResult<T> {
+  let value: [u8; SIZE] = bytes
+    .get(*index..*index + SIZE)
+    .and_then(|bytes| bytes.try_into().ok())
+    .ok_or_else(|| DecoderError::not_enough_bytes(type_name::<T>(), *index))?;
+
+  *index += SIZE;
+
+  Ok(T::from_bytes_of(endian, value))
+}
+
+/// Default for [`ByteDecoder::with_max_len`].
+pub const DEFAULT_MAX_LEN: usize = u32::MAX as usize;
+
+/// Default for [`ByteDecoder::with_max_depth`].
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+pub struct ByteDecoder<'a, C: Codec = FixedCodec> {
   bytes: &'a [u8],
   endian: ByteEndian,
   index: usize,
+  max_len: usize,
+  depth: usize,
+  max_depth: usize,
+  varint_lengths: bool,
+  strict_bools: bool,
+  strict_floats: bool,
+  _codec: PhantomData<C>,
 }
 
 impl<'a> ByteDecoder<'a> {
   pub fn new(bytes: &'a [u8], endian: ByteEndian) -> Self {
-    Self { bytes, endian, index: 0 }
+    Self::with_codec(bytes, endian)
   }
 
-  pub fn bytes(&self) -> &[u8] { &self.bytes }
+  /// Reads the length of a serialized `Vec<T>` and skips over its elements,
+  /// which can then be decoded one at a time through the returned [`SliceView`].
+  ///
+  /// Fails with [`DecoderError::Unsupported`] with `varint_lengths`, a `usize` anywhere in `T` has no fixed size then.
+  pub fn decode_slice_view<T: FixedSize + Deserializer>(&mut self) -> DecoderResult<SliceView<'a, T>> {
+    if self.varint_lengths {
+      return Err(DecoderError::unsupported("slice views with varint lengths"));
+    }
 
-  fn read_bytes<T: EndianValue<SIZE>, const SIZE: usize>(&mut self) -> DecoderResult<T> {
-    let value: [u8; SIZE] = self
-      .bytes
-      .get(self.index..self.index + SIZE)
-      .and_then(|bytes| bytes.try_into().ok())
-      .ok_or_else(|| DecoderError::not_enough_bytes(type_name::<T>(), self.index))?;
+    let len = self.decode_usize()?;
+    let size = len
+      .checked_mul(T::WIRE_SIZE)
+      .ok_or_else(|| DecoderError::length_too_large(len, usize::MAX / T::WIRE_SIZE.max(1)))?;
 
-    self.index += SIZE;
+    // elements are decoded with the same settings as everything else
+    let decoder = Self { bytes: self.read_slice(size)?, index: 0, ..*self };
 
-    Ok(T::from_bytes_of(self.endian, value))
+    Ok(SliceView { decoder, len, _marker: PhantomData })
   }
 }
 
-impl<'a> Decoder for ByteDecoder<'a> {
-  fn decode_u8(&mut self) -> DecoderResult<u8> { self.read_bytes() }
-  fn decode_u16(&mut self) -> DecoderResult<u16> { self.read_bytes() }
-  fn decode_u32(&mut self) -> DecoderResult<u32> { self.read_bytes() }
-  fn decode_u64(&mut self) -> DecoderResult<u64> { self.read_bytes() }
-  fn decode_u128(&mut self) -> DecoderResult<u128> { self.read_bytes() }
+impl<'a, C: Codec> ByteDecoder<'a, C> {
+  pub fn with_codec(bytes: &'a [u8], endian: ByteEndian) -> Self {
+    Self {
+      bytes,
+      endian,
+      index: 0,
+      max_len: DEFAULT_MAX_LEN,
+      depth: 0,
+      max_depth: DEFAULT_MAX_DEPTH,
+      varint_lengths: false,
+      strict_bools: false,
+      strict_floats: false,
+      _codec: PhantomData,
+    }
+  }
 
-  fn decode_i8(&mut self) -> DecoderResult<i8> { self.read_bytes() }
-  fn decode_i16(&mut self) -> DecoderResult<i16> { self.read_bytes() }
-  fn decode_i32(&mut self) -> DecoderResult<i32> { self.read_bytes() }
-  fn decode_i64(&mut self) -> DecoderResult<i64> { self.read_bytes() }
-  fn decode_i128(&mut self) -> DecoderResult<i128> { self.read_bytes() }
+  /// Maximum element count of a decoded slice, string or map, anything longer is [`DecoderError::LengthTooLarge`].
+  pub fn with_max_len(mut self, max_len: usize) -> Self {
+    self.max_len = max_len;
+    self
+  }
 
-  fn decode_f32(&mut self) -> DecoderResult<f32> { self.read_bytes() }
-  fn decode_f64(&mut self) -> DecoderResult<f64> { self.read_bytes() }
+  /// How deep values (struct fields, slice elements, boxes, ...) may nest before decoding
+  /// fails with [`DecoderError::RecursionLimitExceeded`] instead of overflowing the stack.
+  pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+    self.max_depth = max_depth;
+    self
+  }
 
-  fn decode_slice<T: Deserializer>(&mut self) -> DecoderResult<Vec<T>> {
-    let len = self.decode_usize()?;
-    let mut vec = Vec::new();
+  /// Inverse of [`ByteEncoder::with_varint_lengths`](crate::encoder::ByteEncoder::with_varint_lengths).
+  pub fn with_varint_lengths(mut self) -> Self {
+    self.varint_lengths = true;
+    self
+  }
 
-    vec.try_reserve_exact(len)
-      .map_err(|_| DecoderError::not_enough_memory_for_slice(len, self.index))?;
+  /// Rejects bools that aren't `0` or `1` with [`DecoderError::InvalidBool`], instead of reading anything nonzero as `true`.
+  pub fn with_strict_bools(mut self) -> Self {
+    self.strict_bools = true;
+    self
+  }
 
-    for _ in 0..len {
-      vec.push(T::decode(self)?);
+  /// Rejects NaN and infinite floats with [`DecoderError::NonFiniteFloat`], for inputs where they can only mean corruption.
+  pub fn with_strict_floats(mut self) -> Self {
+    self.strict_floats = true;
+    self
+  }
+
+  pub fn bytes(&self) -> &[u8] { self.bytes }
+
+  pub fn position(&self) -> usize { self.index }
+
+  pub fn remaining(&self) -> usize { self.bytes.len() - self.index }
+
+  pub fn remaining_bytes(&self) -> &'a [u8] { &self.bytes[self.index..] }
+
+  /// Moves to `position`, which may be anywhere up to and including the end of the input.
+  pub fn seek(&mut self, position: usize) -> DecoderResult<()> {
+    if position > self.bytes.len() {
+      return Err(DecoderError::index_out_of_bounds(position, self.bytes.len()));
     }
 
-    Ok(vec)
+    self.index = position;
+
+    Ok(())
   }
 
-  fn decode_string(&mut self) -> DecoderResult<String> {
-    let data = self.decode_slice::<u16>()?;
+  /// Reads the length prefix of the next slice, string or map without advancing.
+  pub fn peek_len(&self) -> DecoderResult<usize> {
+    Self { ..*self }.decode_usize()
+  }
 
-    String::from_utf16(&data).map_err(|_| DecoderError::invalid_utf16(self.index))
+  /// The next byte as is, without advancing.
+  pub fn peek_u8(&self) -> DecoderResult<u8> {
+    self.peek_bytes::<1>().map(|[byte]| byte)
   }
 
-  fn decode_map<K: Deserializer + Eq + Hash, V: Deserializer>(&mut self) -> DecoderResult<HashMap<K, V>> {
-    let entries = self.decode_slice::<MapEntry<K, V>>()?;
-    let mut map = HashMap::with_capacity(entries.len());
+  /// The next `N` bytes as they are, without advancing.
+  pub fn peek_bytes<const N: usize>(&self) -> DecoderResult<[u8; N]> {
+    Self { ..*self }.read_slice(N).map(|bytes| bytes.try_into().unwrap())
+  }
 
-    for entry in entries {
-      map.insert(entry.0, entry.1);
+  /// Reads a length prefixed slice of bytes without copying it.
+  pub fn decode_bytes(&mut self) -> DecoderResult<&'a [u8]> {
+    let len = self.decode_usize()?;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
     }
 
-    Ok(map)
+    self.read_slice(len)
   }
 
-  fn decode_value<T: Deserializer>(&mut self) -> DecoderResult<T> {
-    T::decode(self)
+  /// Reads a string without copying it, checking it's valid UTF-8.
+  ///
+  /// [`DecoderError::InvalidUTF8`] points at the first byte that isn't, relative to the whole input.
+  #[cfg(not(feature = "utf16_strings"))]
+  pub fn decode_str(&mut self) -> DecoderResult<&'a str> {
+    let len = self.decode_usize()?;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    let start = self.index;
+    let bytes = self.read_slice(len)?;
+
+    core::str::from_utf8(bytes).map_err(|err| DecoderError::invalid_utf8(start + err.valid_up_to()))
   }
-}
 
-pub trait FromBytes: Deserializer + Sized {
-  fn from_bytes(bytes: &[u8], endian: ByteEndian) -> DecoderResult<Self> {
-    let mut decoder = ByteDecoder::new(bytes, endian);
-    Ok(Self::decode(&mut decoder)?)
+  /// Same as [`ByteDecoder::decode_str`].
+  #[cfg(not(feature = "utf16_strings"))]
+  pub fn decode_str_validate(&mut self) -> DecoderResult<&'a str> {
+    self.decode_str()
   }
-}
 
-impl<T: Deserializer> FromBytes for T {}
+  fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> DecoderResult<T>) -> DecoderResult<T> {
+    if self.depth >= self.max_depth {
+      return Err(DecoderError::recursion_limit_exceeded(self.max_depth));
+    }
 
-pub trait Deserializer: Sized {
-  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self>;
-}
+    self.depth += 1;
+    let value = f(self);
+    self.depth -= 1;
 
-impl<T: Deserializer> Deserializer for Vec<T> {
-  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
-    decoder.decode_slice()
+    value
   }
-}
 
-impl<K: Deserializer + Eq + Hash, V: Deserializer> Deserializer for HashMap<K, V> {
-  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
-    decoder.decode_map()
+  fn check_finite<T: Float>(&self, value: T) -> DecoderResult<T> {
+    match value.finite() || !self.strict_floats {
+      true => Ok(value),
+      false => Err(DecoderError::non_finite_float(type_name::<T>())),
+    }
   }
-}
 
-macro_rules! impl_deserializer_tuple {
-  ($($name:ident),+) => {
-    impl <$($name: Deserializer),+> Deserializer for ($($name),+) {
-      fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
-        Ok(($(decoder.decode_value::<$name>()?),+))
-      }
-    }
-  };
-}
+  fn read_slice(&mut self, len: usize) -> DecoderResult<&'a [u8]> {
+    let bytes = self.index
+      .checked_add(len)
+      .and_then(|end| self.bytes.get(self.index..end))
+      .ok_or_else(|| DecoderError::not_enough_bytes(format!("[u8; {}]", len), self.index))?;
 
+    self.index += len;
+
+    Ok(bytes)
+  }
+
+  fn read_bytes<T: EndianValue<SIZE>, const SIZE: usize>(&mut self) -> DecoderResult<T> {
+    read_fixed(self.bytes, &mut self.index, self.endian)
+  }
+
+  fn read_int<T: Integer<SIZE>, const SIZE: usize>(&mut self) -> DecoderResult<T> {
+    C::read(self.bytes, &mut self.index, self.endian)
+  }
+}
+
+/// Floats [`ByteDecoder::with_strict_floats`] rejects NaN and infinities of.
+trait Float: Copy {
+  fn finite(self) -> bool;
+}
+
+impl Float for f32 {
+  fn finite(self) -> bool { self.is_finite() }
+}
+
+impl Float for f64 {
+  fn finite(self) -> bool { self.is_finite() }
+}
+
+#[cfg(feature = "half")]
+impl Float for f16 {
+  fn finite(self) -> bool { self.is_finite() }
+}
+
+/// Lazy view over a serialized `Vec<T>` of fixed size elements, see [`ByteDecoder::decode_slice_view`].
+pub struct SliceView<'a, T> {
+  decoder: ByteDecoder<'a>,
+  len: usize,
+  _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: FixedSize + Deserializer> SliceView<'a, T> {
+  pub fn len(&self) -> usize { self.len }
+
+  pub fn is_empty(&self) -> bool { self.len == 0 }
+
+  /// Decodes only the element at `index`.
+  pub fn get(&self, index: usize) -> DecoderResult<T> {
+    if index >= self.len {
+      return Err(DecoderError::index_out_of_bounds(index, self.len));
+    }
+
+    let start = index * T::WIRE_SIZE;
+    let mut decoder = ByteDecoder { bytes: &self.decoder.bytes[..start + T::WIRE_SIZE], index: start, ..self.decoder };
+
+    decoder.decode_value()
+  }
+}
+
+impl<'a, C: Codec> Decoder for ByteDecoder<'a, C> {
+  fn endian(&self) -> ByteEndian { self.endian }
+  fn set_endian(&mut self, endian: ByteEndian) { self.endian = endian; }
+  fn at_end(&mut self) -> bool { self.index == self.bytes.len() }
+  fn position(&self) -> usize { self.index }
+
+  fn decode_block<T>(&mut self, f: impl FnOnce(&mut Self) -> DecoderResult<T>) -> DecoderResult<T> {
+    let len = self.decode_usize()?;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    let end = self.index
+      .checked_add(len)
+      .filter(|&end| end <= self.bytes.len())
+      .ok_or_else(|| DecoderError::not_enough_bytes(format!("[u8; {}]", len), self.index))?;
+
+    // the input is cut off after the block for as long as `f` runs
+    let bytes = self.bytes;
+    self.bytes = &bytes[..end];
+
+    let value = self.nested(f);
+    self.bytes = bytes;
+
+    self.index = end;
+
+    value
+  }
+
+  fn decode_u8(&mut self) -> DecoderResult<u8> { self.read_int() }
+  fn decode_u16(&mut self) -> DecoderResult<u16> { self.read_int() }
+  fn decode_u32(&mut self) -> DecoderResult<u32> { self.read_int() }
+  fn decode_u64(&mut self) -> DecoderResult<u64> { self.read_int() }
+  fn decode_u128(&mut self) -> DecoderResult<u128> { self.read_int() }
+
+  fn decode_i8(&mut self) -> DecoderResult<i8> { self.read_int() }
+  fn decode_i16(&mut self) -> DecoderResult<i16> { self.read_int() }
+  fn decode_i32(&mut self) -> DecoderResult<i32> { self.read_int() }
+  fn decode_i64(&mut self) -> DecoderResult<i64> { self.read_int() }
+  fn decode_i128(&mut self) -> DecoderResult<i128> { self.read_int() }
+
+  fn decode_f32(&mut self) -> DecoderResult<f32> {
+    let value: f32 = self.read_bytes()?;
+    self.check_finite(value)
+  }
+
+  fn decode_f64(&mut self) -> DecoderResult<f64> {
+    let value: f64 = self.read_bytes()?;
+    self.check_finite(value)
+  }
+
+  #[cfg(feature = "half")]
+  fn decode_f16(&mut self) -> DecoderResult<f16> {
+    let value = self.decode_u16().map(f16::from_bits)?;
+    self.check_finite(value)
+  }
+
+  fn decode_bool(&mut self) -> DecoderResult<bool> {
+    match self.decode_u8()? {
+      0 => Ok(false),
+      1 => Ok(true),
+      value if self.strict_bools => Err(DecoderError::invalid_bool(value)),
+      _ => Ok(true),
+    }
+  }
+
+  fn decode_usize(&mut self) -> DecoderResult<usize> {
+    match self.varint_lengths {
+      true => self.decode_varint_u64().map(|it| it as usize),
+      false => self.decode_u64().map(|it| it as usize),
+    }
+  }
+
+  fn decode_sorted_ints(&mut self) -> DecoderResult<Vec<u64>> {
+    let len = self.decode_varint_u64()? as usize;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    // every value takes up at least a byte
+    let capacity = len.min(self.bytes.len() - self.index);
+    let mut vec = Vec::new();
+
+    vec.try_reserve_exact(capacity)
+      .map_err(|_| DecoderError::not_enough_memory_for_slice(len, self.index))?;
+
+    read_sorted_ints(self, len, vec)
+  }
+
+  fn decode_slice<T: Deserializer>(&mut self) -> DecoderResult<Vec<T>> {
+    let len = self.decode_usize()?;
+    let limit = isize::MAX as usize / size_of::<T>().max(1);
+
+    if len.checked_mul(size_of::<T>()).filter(|&size| size <= isize::MAX as usize).is_none() {
+      return Err(DecoderError::length_too_large(len, limit));
+    }
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    // `usize` elements are varints as well with `varint_lengths`
+    let fixed_primitive = T::IS_PRIMITIVE && C::FIXED_WIDTH && !self.varint_lengths;
+
+    // the size of every element is known, a length the input can't hold fails before anything is decoded
+    if fixed_primitive {
+      let available = (self.bytes.len() - self.index) / size_of::<T>();
+
+      if len > available {
+        return Err(DecoderError::not_enough_bytes(type_name::<T>(), self.index + available * size_of::<T>()));
+      }
+    }
+
+    // don't trust `len` for the allocation, the remaining input can't hold more elements than it has bytes
+    // (bar ones that encode to nothing, the vec just grows for those)
+    let capacity = len.min(self.bytes.len() - self.index);
+    let mut vec = Vec::new();
+
+    vec.try_reserve_exact(capacity)
+      .map_err(|_| DecoderError::not_enough_memory_for_slice(len, self.index))?;
+
+    // strict floats have to be checked one at a time
+    if fixed_primitive && self.endian.is_native() && !(T::IS_FLOAT && self.strict_floats) {
+      let end = self.index + len * size_of::<T>();
+      vec.extend(T::decode_native_slice(&self.bytes[self.index..end])?);
+      self.index = end;
+
+      return Ok(vec);
+    }
+
+    self.nested(|decoder| {
+      for _ in 0..len {
+        vec.push(T::decode(decoder)?);
+      }
+
+      Ok(vec)
+    })
+  }
+
+  fn decode_value<T: Deserializer>(&mut self) -> DecoderResult<T> {
+    self.nested(T::decode)
+  }
+
+  #[cfg(not(feature = "utf16_strings"))]
+  fn decode_string(&mut self) -> DecoderResult<String> {
+    let data = self.decode_byte_vec()?;
+    let start = self.index - data.len();
+
+    String::from_utf8(data).map_err(|err| DecoderError::invalid_utf8(start + err.utf8_error().valid_up_to()))
+  }
+
+  #[cfg(feature = "utf16_strings")]
+  fn decode_string(&mut self) -> DecoderResult<String> {
+    let data = self.decode_slice::<u16>()?;
+
+    String::from_utf16(&data).map_err(|_| DecoderError::invalid_utf16(self.index))
+  }
+
+  fn decode_byte_vec(&mut self) -> DecoderResult<Vec<u8>> {
+    if !C::FIXED_WIDTH {
+      return self.decode_slice();
+    }
+
+    let len = self.decode_usize()?;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    Ok(self.read_slice(len)?.to_vec())
+  }
+
+  fn decode_fixed_bytes(&mut self, len: usize) -> DecoderResult<Vec<u8>> {
+    if !C::FIXED_WIDTH {
+      return (0..len).map(|_| self.decode_u8()).collect();
+    }
+
+    Ok(self.read_slice(len)?.to_vec())
+  }
+}
+
+/// Decodes from any [`Read`] through a [`BufReader`], pulling bytes in as values need them
+/// instead of requiring the whole input up front.
+#[cfg(feature = "std")]
+pub struct ReadDecoder<R: Read> {
+  reader: BufReader<R>,
+  endian: ByteEndian,
+  position: usize,
+  max_len: usize,
+  /// Position the current block ends at, see [`Decoder::decode_block`].
+  limit: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> ReadDecoder<R> {
+  /// Elements reserved up front for a slice, the rest is only allocated as elements actually arrive.
+  const PREALLOCATE: usize = 1024;
+
+  pub fn new(reader: R, endian: ByteEndian) -> Self {
+    Self { reader: BufReader::new(reader), endian, position: 0, max_len: DEFAULT_MAX_LEN, limit: usize::MAX }
+  }
+
+  /// Same as [`ByteDecoder::with_max_len`].
+  pub fn with_max_len(mut self, max_len: usize) -> Self {
+    self.max_len = max_len;
+    self
+  }
+
+  /// Amount of bytes decoded so far.
+  pub fn position(&self) -> usize { self.position }
+
+  pub fn into_inner(self) -> BufReader<R> { self.reader }
+
+  fn read_bytes<T: EndianValue<SIZE>, const SIZE: usize>(&mut self) -> DecoderResult<T> {
+    let mut value = [0u8; SIZE];
+    self.fill(&mut value, type_name::<T>())?;
+
+    Ok(T::from_bytes_of(self.endian, value))
+  }
+
+  fn fill(&mut self, bytes: &mut [u8], type_name: &str) -> DecoderResult<()> {
+    if bytes.len() > self.limit - self.position {
+      return Err(DecoderError::not_enough_bytes(type_name, self.position));
+    }
+
+    let mut filled = 0;
+
+    while filled < bytes.len() {
+      match self.reader.read(&mut bytes[filled..]) {
+        Ok(0) => return Err(DecoderError::not_enough_bytes(type_name, self.position + filled)),
+        Ok(len) => filled += len,
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+        Err(err) => return Err(DecoderError::io(err)),
+      }
+    }
+
+    self.position += filled;
+
+    Ok(())
+  }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read> Decoder for ReadDecoder<R> {
+  fn endian(&self) -> ByteEndian { self.endian }
+  fn set_endian(&mut self, endian: ByteEndian) { self.endian = endian; }
+  fn position(&self) -> usize { self.position }
+
+  fn at_end(&mut self) -> bool {
+    self.position == self.limit || self.reader.fill_buf().is_ok_and(|bytes| bytes.is_empty())
+  }
+
+  fn decode_block<T>(&mut self, f: impl FnOnce(&mut Self) -> DecoderResult<T>) -> DecoderResult<T> {
+    let len = self.decode_usize()?;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    let end = self.position
+      .checked_add(len)
+      .filter(|&end| end <= self.limit)
+      .ok_or_else(|| DecoderError::not_enough_bytes(format!("[u8; {}]", len), self.position))?;
+
+    let limit = core::mem::replace(&mut self.limit, end);
+    let value = f(self);
+    self.limit = limit;
+
+    let value = value?;
+
+    // skip what `f` didn't read
+    let rest = (end - self.position) as u64;
+    let skipped = io::copy(&mut self.reader.by_ref().take(rest), &mut io::sink()).map_err(DecoderError::io)?;
+    self.position += skipped as usize;
+
+    if skipped < rest {
+      return Err(DecoderError::not_enough_bytes("u8", self.position));
+    }
+
+    Ok(value)
+  }
+
+  fn decode_u8(&mut self) -> DecoderResult<u8> { self.read_bytes() }
+  fn decode_u16(&mut self) -> DecoderResult<u16> { self.read_bytes() }
+  fn decode_u32(&mut self) -> DecoderResult<u32> { self.read_bytes() }
+  fn decode_u64(&mut self) -> DecoderResult<u64> { self.read_bytes() }
+  fn decode_u128(&mut self) -> DecoderResult<u128> { self.read_bytes() }
+
+  fn decode_i8(&mut self) -> DecoderResult<i8> { self.read_bytes() }
+  fn decode_i16(&mut self) -> DecoderResult<i16> { self.read_bytes() }
+  fn decode_i32(&mut self) -> DecoderResult<i32> { self.read_bytes() }
+  fn decode_i64(&mut self) -> DecoderResult<i64> { self.read_bytes() }
+  fn decode_i128(&mut self) -> DecoderResult<i128> { self.read_bytes() }
+
+  fn decode_f32(&mut self) -> DecoderResult<f32> { self.read_bytes() }
+  fn decode_f64(&mut self) -> DecoderResult<f64> { self.read_bytes() }
+
+  fn decode_sorted_ints(&mut self) -> DecoderResult<Vec<u64>> {
+    let len = self.decode_varint_u64()? as usize;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    read_sorted_ints(self, len, Vec::with_capacity(len.min(Self::PREALLOCATE)))
+  }
+
+  fn decode_slice<T: Deserializer>(&mut self) -> DecoderResult<Vec<T>> {
+    let len = self.decode_usize()?;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    let mut vec = Vec::with_capacity(len.min(Self::PREALLOCATE));
+
+    for _ in 0..len {
+      vec.push(T::decode(self)?);
+    }
+
+    Ok(vec)
+  }
+
+  #[cfg(not(feature = "utf16_strings"))]
+  fn decode_string(&mut self) -> DecoderResult<String> {
+    let data = self.decode_byte_vec()?;
+    let start = self.position - data.len();
+
+    String::from_utf8(data).map_err(|err| DecoderError::invalid_utf8(start + err.utf8_error().valid_up_to()))
+  }
+
+  #[cfg(feature = "utf16_strings")]
+  fn decode_string(&mut self) -> DecoderResult<String> {
+    let data = self.decode_slice::<u16>()?;
+
+    String::from_utf16(&data).map_err(|_| DecoderError::invalid_utf16(self.position))
+  }
+
+  fn decode_byte_vec(&mut self) -> DecoderResult<Vec<u8>> {
+    let len = self.decode_usize()?;
+
+    if len > self.max_len {
+      return Err(DecoderError::length_too_large(len, self.max_len));
+    }
+
+    let mut vec = Vec::with_capacity(len.min(Self::PREALLOCATE));
+
+    // grown a chunk at a time, same as `decode_slice` a bogus length runs out of input before memory
+    while vec.len() < len {
+      let start = vec.len();
+      vec.resize(start + (len - start).min(Self::PREALLOCATE), 0);
+      self.fill(&mut vec[start..], "u8")?;
+    }
+
+    Ok(vec)
+  }
+}
+
+pub trait FromBytes: Deserializer + Sized {
+  fn from_bytes(bytes: &[u8], endian: ByteEndian) -> DecoderResult<Self> {
+    let mut decoder = ByteDecoder::new(bytes, endian);
+    Self::decode(&mut decoder)
+  }
+
+  /// Like `from_bytes`, but also returns how many bytes the value took up,
+  /// which is where the next value starts in a buffer of back to back values.
+  fn from_bytes_with_len(bytes: &[u8], endian: ByteEndian) -> DecoderResult<(Self, usize)> {
+    let mut decoder = ByteDecoder::new(bytes, endian);
+    let value = Self::decode(&mut decoder)?;
+
+    Ok((value, decoder.position()))
+  }
+
+  /// Like `from_bytes`, but rejects any input left over after the value.
+  fn from_bytes_exact(bytes: &[u8], endian: ByteEndian) -> DecoderResult<Self> {
+    let mut decoder = ByteDecoder::new(bytes, endian);
+    let value = Self::decode(&mut decoder)?;
+
+    match bytes.len() - decoder.position() {
+      0 => Ok(value),
+      remaining => Err(DecoderError::trailing_bytes(remaining)),
+    }
+  }
+
+  /// Decodes a single value from `reader`, see [`ReadDecoder`].
+  ///
+  /// Input after the value may have been buffered and is lost, use a `ReadDecoder` directly to read more than one.
+  #[cfg(feature = "std")]
+  fn from_reader(reader: impl Read, endian: ByteEndian) -> DecoderResult<Self> {
+    let mut decoder = ReadDecoder::new(reader, endian);
+    Self::decode(&mut decoder)
+  }
+
+  /// Like `from_bytes`, but re-encodes the value and rejects the input unless it's byte-identical,
+  /// so only the one canonical encoding of a value (and no trailing bytes) is accepted.
+  fn from_bytes_canonical(bytes: &[u8], endian: ByteEndian) -> DecoderResult<Self> where Self: Serializer {
+    let value = Self::from_bytes(bytes, endian)?;
+    let canonical = value.to_bytes(endian);
+
+    if canonical != bytes {
+      let index = canonical.iter()
+        .zip(bytes)
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| canonical.len().min(bytes.len()));
+
+      return Err(DecoderError::non_canonical(index));
+    }
+
+    Ok(value)
+  }
+}
+
+impl<T: Deserializer> FromBytes for T {}
+
+pub trait Deserializer: Sized {
+  /// Set for integers and floats whose in-memory layout matches their wire format,
+  /// [`ByteDecoder`] copies slices of those straight out of native-endian input.
+  #[doc(hidden)]
+  const IS_PRIMITIVE: bool = false;
+
+  /// Set for floats, which [`ByteDecoder::with_strict_floats`] has to check one at a time.
+  #[doc(hidden)]
+  const IS_FLOAT: bool = false;
+
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self>;
+
+  /// Decodes every value of `bytes` in native endianness, only called when `IS_PRIMITIVE` is set.
+  ///
+  /// Decodes them one at a time unless overridden, setting `IS_PRIMITIVE` on anything else only makes it slower.
+  #[doc(hidden)]
+  fn decode_native_slice(bytes: &[u8]) -> DecoderResult<Vec<Self>> {
+    let mut decoder = ByteDecoder::new(bytes, ByteEndian::Native);
+
+    (0..bytes.len() / size_of::<Self>().max(1)).map(|_| decoder.decode_value()).collect()
+  }
+
+  /// Decodes a length prefixed run of values, `u8` goes through [`Decoder::decode_byte_vec`] instead.
+  #[doc(hidden)]
+  fn decode_many(decoder: &mut impl Decoder) -> DecoderResult<Vec<Self>> {
+    decoder.decode_slice()
+  }
+}
+
+/// Decodes values that can borrow from the input of a [`ByteDecoder`] instead of allocating,
+/// every [`Deserializer`] is trivially a `BorrowedDeserializer`.
+pub trait BorrowedDeserializer<'a>: Sized {
+  fn decode_borrowed(decoder: &mut ByteDecoder<'a>) -> DecoderResult<Self>;
+}
+
+impl<'a, T: Deserializer> BorrowedDeserializer<'a> for T {
+  fn decode_borrowed(decoder: &mut ByteDecoder<'a>) -> DecoderResult<Self> {
+    T::decode(decoder)
+  }
+}
+
+impl<'a> BorrowedDeserializer<'a> for &'a [u8] {
+  fn decode_borrowed(decoder: &mut ByteDecoder<'a>) -> DecoderResult<Self> {
+    decoder.decode_bytes()
+  }
+}
+
+#[cfg(not(feature = "utf16_strings"))]
+impl<'a> BorrowedDeserializer<'a> for &'a str {
+  fn decode_borrowed(decoder: &mut ByteDecoder<'a>) -> DecoderResult<Self> {
+    decoder.decode_str()
+  }
+}
+
+/// Counterpart of [`EnumSerializer`](crate::encoder::EnumSerializer),
+/// `decode` for a derived enum is always `decode_tag` followed by `decode_payload`.
+pub trait EnumDeserializer: Deserializer {
+  type Tag: Deserializer;
+
+  fn decode_tag(decoder: &mut impl Decoder) -> DecoderResult<Self::Tag> {
+    decoder.decode_value()
+  }
+
+  fn decode_payload(tag: Self::Tag, decoder: &mut impl Decoder) -> DecoderResult<Self>;
+}
+
+impl<T: Deserializer> Deserializer for Vec<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    T::decode_many(decoder)
+  }
+}
+
+impl<T: Deserializer, const N: usize> Deserializer for [T; N] {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let mut vec = Vec::with_capacity(N);
+
+    for _ in 0..N {
+      vec.push(decoder.decode_value()?);
+    }
+
+    Ok(vec.try_into().unwrap_or_else(|_| unreachable!()))
+  }
+}
+
+#[cfg(feature = "std")]
+impl<K: Deserializer + Eq + Hash, V: Deserializer, S: BuildHasher + Default> Deserializer for HashMap<K, V, S> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decoder.decode_map()
+  }
+}
+
+#[cfg(feature = "std")]
+impl<T: Deserializer + Eq + Hash, S: BuildHasher + Default> Deserializer for HashSet<T, S> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let values = T::decode_many(decoder)?;
+    let mut set = HashSet::with_capacity_and_hasher(values.len(), S::default());
+
+    set.extend(values);
+
+    Ok(set)
+  }
+}
+
+impl<K: Deserializer + Ord, V: Deserializer> Deserializer for BTreeMap<K, V> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let entries = decoder.decode_slice::<MapEntry<K, V>>()?;
+
+    Ok(entries.into_iter().map(|MapEntry(key, value)| (key, value)).collect())
+  }
+}
+
+impl<T: Deserializer + Ord> Deserializer for BTreeSet<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    Ok(T::decode_many(decoder)?.into_iter().collect())
+  }
+}
+
+impl<T: Deserializer> Deserializer for VecDeque<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    T::decode_many(decoder).map(VecDeque::from)
+  }
+}
+
+impl<T: Deserializer> Deserializer for LinkedList<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    Ok(T::decode_many(decoder)?.into_iter().collect())
+  }
+}
+
+/// Heap order is rebuilt, not taken from the input.
+impl<T: Deserializer + Ord> Deserializer for BinaryHeap<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    T::decode_many(decoder).map(BinaryHeap::from)
+  }
+}
+
+impl<T: Deserializer> Deserializer for Option<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    match decoder.decode_u8()? {
+      0 => Ok(None),
+      1 => Ok(Some(decoder.decode_value()?)),
+      tag => Err(DecoderError::invalid_variant(type_name::<Self>(), tag as u64, 2))
+    }
+  }
+}
+
+impl<T: Deserializer, E: Deserializer> Deserializer for Result<T, E> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    match decoder.decode_u8()? {
+      0 => Ok(Ok(decoder.decode_value()?)),
+      1 => Ok(Err(decoder.decode_value()?)),
+      tag => Err(DecoderError::invalid_variant(type_name::<Self>(), tag as u64, 2))
+    }
+  }
+}
+
+/// Rejects `nanos >= 1_000_000_000` rather than letting `Duration::new` carry them into the seconds,
+/// so every accepted input is exactly what the encoder would write.
+impl Deserializer for Duration {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let secs = decoder.decode_u64()?;
+    let nanos = decoder.decode_u32()?;
+
+    if nanos >= 1_000_000_000 {
+      return Err(DecoderError::invalid_duration(nanos));
+    }
+
+    Ok(Duration::new(secs, nanos))
+  }
+}
+
+impl<T: Deserializer> Deserializer for Range<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    Ok(decoder.decode_value()?..decoder.decode_value()?)
+  }
+}
+
+impl<T: Deserializer> Deserializer for RangeInclusive<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    Ok(RangeInclusive::new(decoder.decode_value()?, decoder.decode_value()?))
+  }
+}
+
+#[cfg(feature = "std")]
+impl Deserializer for Ipv4Addr {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decoder.decode_value::<[u8; 4]>().map(Ipv4Addr::from)
+  }
+}
+
+#[cfg(feature = "std")]
+impl Deserializer for Ipv6Addr {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decoder.decode_value::<[u8; 16]>().map(Ipv6Addr::from)
+  }
+}
+
+#[cfg(feature = "std")]
+impl Deserializer for IpAddr {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    match decoder.decode_u8()? {
+      0 => Ok(IpAddr::V4(decoder.decode_value()?)),
+      1 => Ok(IpAddr::V6(decoder.decode_value()?)),
+      tag => Err(DecoderError::invalid_variant(type_name::<Self>(), tag as u64, 2))
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl Deserializer for SocketAddrV4 {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let ip = decoder.decode_value()?;
+    let port = decoder.with_endian(ByteEndian::Big, |decoder| decoder.decode_u16())?;
+
+    Ok(SocketAddrV4::new(ip, port))
+  }
+}
+
+#[cfg(feature = "std")]
+impl Deserializer for SocketAddrV6 {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let ip = decoder.decode_value()?;
+    let (port, flowinfo, scope_id) = decoder.with_endian(ByteEndian::Big, |decoder| {
+      Ok::<_, DecoderError>((decoder.decode_u16()?, decoder.decode_u32()?, decoder.decode_u32()?))
+    })?;
+
+    Ok(SocketAddrV6::new(ip, port, flowinfo, scope_id))
+  }
+}
+
+#[cfg(feature = "std")]
+impl Deserializer for SocketAddr {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    match decoder.decode_u8()? {
+      0 => Ok(SocketAddr::V4(decoder.decode_value()?)),
+      1 => Ok(SocketAddr::V6(decoder.decode_value()?)),
+      tag => Err(DecoderError::invalid_variant(type_name::<Self>(), tag as u64, 2))
+    }
+  }
+}
+
+fn decode_weak_presence(decoder: &mut impl Decoder) -> DecoderResult<()> {
+  match decoder.decode_u8()? {
+    0 => Ok(()),
+    _ => Err(DecoderError::custom("weak reference encoded as present"))
+  }
+}
+
+/// Always decodes to `Cow::Owned`.
+impl<B: ToOwned + ?Sized> Deserializer for Cow<'_, B> where B::Owned: Deserializer {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decoder.decode_value().map(Cow::Owned)
+  }
+}
+
+macro_rules! impl_deserializer_pointer {
+  ($($pointer:ident),+) => {
+    $(/// Every pointer gets its own allocation, sharing isn't restored.
+    impl<T: Deserializer> Deserializer for $pointer<T> {
+      fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+        decoder.decode_value().map($pointer::new)
+      }
+    }
+
+    impl Deserializer for $pointer<str> {
+      fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+        decoder.decode_string().map($pointer::from)
+      }
+    })+
+  };
+}
+
+impl_deserializer_pointer!(Box, Rc, Arc);
+
+/// Always decodes to an empty `Weak::new()`, the link has to be rebuilt after decoding.
+impl<T> Deserializer for rc::Weak<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decode_weak_presence(decoder).map(|_| rc::Weak::new())
+  }
+}
+
+/// Always decodes to an empty `Weak::new()`, the link has to be rebuilt after decoding.
+impl<T> Deserializer for sync::Weak<T> {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decode_weak_presence(decoder).map(|_| sync::Weak::new())
+  }
+}
+
+macro_rules! impl_deserializer_tuple {
+  ($($name:ident),*) => {
+    impl <$($name: Deserializer),*> Deserializer for ($($name,)*) {
+      fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+        Ok(($(decoder.decode_value::<$name>()?,)*))
+      }
+    }
+  };
+}
+
+impl_deserializer_tuple!();
+impl_deserializer_tuple!(A);
 impl_deserializer_tuple!(A, B);
 impl_deserializer_tuple!(A, B, C);
 impl_deserializer_tuple!(A, B, C, D);
@@ -217,6 +1527,13 @@ impl_deserializer_tuple!(A, B, C, D, E, F);
 impl_deserializer_tuple!(A, B, C, D, E, F, G);
 impl_deserializer_tuple!(A, B, C, D, E, F, G, J);
 impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K);
+impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K, L);
+impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K, L, M);
+impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N);
+impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O);
+impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P);
+impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q);
+impl_deserializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q, R);
 
 macro_rules! impl_deserializer {
   ($(($type:ty, $decode:ident)),+ $(,)?) => {
@@ -228,8 +1545,109 @@ macro_rules! impl_deserializer {
   };
 }
 
-impl_deserializer!(
-  (u8, decode_u8), (u16, decode_u16), (u32, decode_u32), (u64, decode_u64), (u128, decode_u128), (usize, decode_usize),
-  (i8, decode_i8), (i16, decode_i16), (i32, decode_i32), (i64, decode_i64), (i128, decode_i128), (isize, decode_isize),
-  (f32, decode_f32), (f64, decode_f64), (bool, decode_bool), (String, decode_string)
-);
\ No newline at end of file
+macro_rules! impl_deserializer_primitive {
+  (@impl $float:literal, $(($type:ty, $decode:ident)),+ $(,)?) => {
+    $(impl Deserializer for $type {
+      // `usize` and `isize` are always 8 bytes on the wire
+      const IS_PRIMITIVE: bool = size_of::<$type>() == <$type as FixedSize>::WIRE_SIZE;
+      const IS_FLOAT: bool = $float;
+
+      fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+        decoder.$decode()
+      }
+
+      fn decode_native_slice(bytes: &[u8]) -> DecoderResult<Vec<Self>> {
+        Ok(bytes.chunks_exact(size_of::<$type>()).map(|chunk| {
+          let mut array = [0; size_of::<$type>()];
+          array.copy_from_slice(chunk);
+
+          Self::from_ne_bytes(array)
+        }).collect())
+      }
+    })+
+  };
+  (float: $($tt:tt)+) => { impl_deserializer_primitive!(@impl true, $($tt)+); };
+  ($($tt:tt)+) => { impl_deserializer_primitive!(@impl false, $($tt)+); };
+}
+
+impl Deserializer for u8 {
+  const IS_PRIMITIVE: bool = true;
+
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decoder.decode_u8()
+  }
+
+  fn decode_native_slice(bytes: &[u8]) -> DecoderResult<Vec<Self>> {
+    Ok(bytes.to_vec())
+  }
+
+  fn decode_many(decoder: &mut impl Decoder) -> DecoderResult<Vec<Self>> {
+    decoder.decode_byte_vec()
+  }
+}
+
+impl_deserializer_primitive!(
+  (u16, decode_u16), (u32, decode_u32), (u64, decode_u64), (u128, decode_u128), (usize, decode_usize),
+  (i8, decode_i8), (i16, decode_i16), (i32, decode_i32), (i64, decode_i64), (i128, decode_i128), (isize, decode_isize)
+);
+
+impl_deserializer_primitive!(float: (f32, decode_f32), (f64, decode_f64));
+
+#[cfg(feature = "half")]
+impl_deserializer_primitive!(float: (f16, decode_f16));
+
+impl_deserializer!((bool, decode_bool), (String, decode_string));
+
+impl<T: ?Sized> Deserializer for PhantomData<T> {
+  fn decode(_decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    Ok(PhantomData)
+  }
+}
+
+impl Deserializer for PhantomPinned {
+  fn decode(_decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    Ok(PhantomPinned)
+  }
+}
+
+macro_rules! impl_deserializer_non_zero {
+  ($(($type:ty, $decode:ident)),+ $(,)?) => {
+    $(impl Deserializer for $type {
+      fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+        <$type>::new(decoder.$decode()?).ok_or_else(|| DecoderError::unexpected_zero(type_name::<$type>()))
+      }
+    })+
+  };
+}
+
+impl_deserializer_non_zero!(
+  (NonZeroU8, decode_u8), (NonZeroU16, decode_u16), (NonZeroU32, decode_u32),
+  (NonZeroU64, decode_u64), (NonZeroU128, decode_u128), (NonZeroUsize, decode_usize),
+  (NonZeroI8, decode_i8), (NonZeroI16, decode_i16), (NonZeroI32, decode_i32),
+  (NonZeroI64, decode_i64), (NonZeroI128, decode_i128), (NonZeroIsize, decode_isize)
+);
+
+macro_rules! impl_deserializer_atomic {
+  ($(($type:ty, $decode:ident)),+ $(,)?) => {
+    $(impl Deserializer for $type {
+      fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+        decoder.$decode().map(<$type>::new)
+      }
+    })+
+  };
+}
+
+impl_deserializer_atomic!(
+  (AtomicU8, decode_u8), (AtomicU16, decode_u16), (AtomicU32, decode_u32), (AtomicU64, decode_u64), (AtomicUsize, decode_usize),
+  (AtomicI8, decode_i8), (AtomicI16, decode_i16), (AtomicI32, decode_i32), (AtomicI64, decode_i64), (AtomicIsize, decode_isize),
+  (AtomicBool, decode_bool)
+);
+
+/// Rejects surrogates and anything past `char::MAX`.
+impl Deserializer for char {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let value = decoder.decode_u32()?;
+
+    char::from_u32(value).ok_or_else(|| DecoderError::invalid_char(value))
+  }
+}
\ No newline at end of file
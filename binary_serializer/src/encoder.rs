@@ -1,13 +1,67 @@
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::io::Write;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use alloc::rc::{self, Rc};
+use alloc::string::{String, ToString};
+use alloc::sync::{self, Arc};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::hash::Hash;
+use core::marker::{PhantomData, PhantomPinned};
+use core::mem::{size_of, size_of_val};
+use core::num::{
+  NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+  NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+};
+use core::ops::{Range, RangeInclusive};
+use core::sync::atomic::{
+  AtomicBool, AtomicI8, AtomicI16, AtomicI32, AtomicI64, AtomicIsize,
+  AtomicU8, AtomicU16, AtomicU32, AtomicU64, AtomicUsize, Ordering,
+};
+use core::time::Duration;
+#[cfg(feature = "half")]
+use half::f16;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
 
 #[cfg(feature = "binary_serializer_derive")]
 pub use binary_serializer_derive::Serializer;
 
-use crate::common::{ByteEndian, EndianValue, MapEntry};
+use crate::codec::{Codec, FixedCodec, Integer};
+use crate::common::{ByteEndian, EndianValue, FixedSize, MapEntry, write_varint, zigzag};
+use crate::size_hint::SizeHint;
 
 pub trait Encoder: Sized {
+  /// Endianness primitives are currently written in.
+  ///
+  /// Only encoders that override it have one to report, others return [`ByteEndian::Native`] and ignore [`Encoder::set_endian`].
+  fn endian(&self) -> ByteEndian {
+    ByteEndian::Native
+  }
+
+  /// Changes the endianness of the primitives that follow, see [`Encoder::with_endian`].
+  fn set_endian(&mut self, endian: ByteEndian) {
+    let _ = endian;
+  }
+
+  /// Runs `f` with `endian` in place of the current endianness, for values that differ from their surroundings.
+  fn with_endian<T>(&mut self, endian: ByteEndian, f: impl FnOnce(&mut Self) -> T) -> T {
+    let previous = self.endian();
+    self.set_endian(endian);
+
+    let value = f(self);
+    self.set_endian(previous);
+
+    value
+  }
+
   fn encode_u8(&mut self, value: u8);
   fn encode_u16(&mut self, value: u16);
   fn encode_u32(&mut self, value: u32);
@@ -25,55 +79,498 @@ pub trait Encoder: Sized {
   fn encode_f32(&mut self, value: f32);
   fn encode_f64(&mut self, value: f64);
 
+  /// Bit pattern written the same as a `u16`.
+  #[cfg(feature = "half")]
+  fn encode_f16(&mut self, value: f16) { self.encode_u16(value.to_bits()); }
+
   fn encode_bool(&mut self, value: bool) {
     self.encode_u8(value as u8);
   }
 
-  fn encode_slice<T: Serializer>(&mut self, value: &[T]);
-  fn encode_string(&mut self, value: impl ToString);
-  fn encode_map<K: Serializer + Eq + Hash, V: Serializer>(&mut self, value: &HashMap<K, V>);
-  fn encode_value<T: Serializer>(&mut self, value: &T);
+  fn encode_varint_u64(&mut self, value: u64) {
+    write_varint(value as u128, |byte| self.encode_u8(byte));
+  }
+
+  /// Zigzag maps `value` onto a `u64` first, so -1, 1, -2, 2 become 1, 2, 3, 4.
+  fn encode_varint_i64(&mut self, value: i64) {
+    self.encode_varint_u64(zigzag(value.into()) as u64);
+  }
+
+  /// Zigzag mapped varints, for signed values that are usually close to zero.
+  fn encode_i8_zigzag(&mut self, value: i8) { write_varint(zigzag(value.into()), |byte| self.encode_u8(byte)); }
+  fn encode_i16_zigzag(&mut self, value: i16) { write_varint(zigzag(value.into()), |byte| self.encode_u8(byte)); }
+  fn encode_i32_zigzag(&mut self, value: i32) { write_varint(zigzag(value.into()), |byte| self.encode_u8(byte)); }
+  fn encode_i64_zigzag(&mut self, value: i64) { write_varint(zigzag(value.into()), |byte| self.encode_u8(byte)); }
+  fn encode_i128_zigzag(&mut self, value: i128) { write_varint(zigzag(value), |byte| self.encode_u8(byte)); }
+  fn encode_isize_zigzag(&mut self, value: isize) { self.encode_i64_zigzag(value as i64); }
+
+  /// Writes the length, the first value and then the difference to the previous value for the rest, all as varints.
+  ///
+  /// Meant for sorted sequences (timestamps, ids), where the deltas are small.
+  /// Deltas are zigzag encoded so unsorted input still round-trips, it just doesn't shrink.
+  fn encode_sorted_ints(&mut self, value: &[u64]) {
+    self.encode_varint_u64(value.len() as u64);
+
+    let mut prev = 0u64;
+
+    for (idx, &value) in value.iter().enumerate() {
+      if idx == 0 {
+        self.encode_varint_u64(value);
+      } else {
+        self.encode_varint_u64(zigzag(value.wrapping_sub(prev) as i64 as i128) as u64);
+      }
+
+      prev = value;
+    }
+  }
+
+  fn encode_slice<T: Serializer>(&mut self, value: &[T]) {
+    self.encode_usize(value.len());
+
+    for value in value {
+      value.encode(self);
+    }
+  }
+
+  /// Same wire format as `encode_slice` of `u8`s, encoders override it to copy the bytes in one go.
+  fn encode_bytes(&mut self, value: &[u8]) {
+    self.encode_slice(value);
+  }
+
+  /// Exactly `len` bytes without a length prefix, `value` is cut off or padded with zeros to fit.
+  fn encode_fixed_bytes(&mut self, value: &[u8], len: usize) {
+    for idx in 0..len {
+      self.encode_u8(value.get(idx).copied().unwrap_or(0));
+    }
+  }
+
+  /// Length prefixed UTF-8 bytes, or UTF-16 code units with the `utf16_strings` feature.
+  fn encode_string(&mut self, value: impl ToString) {
+    let str = value.to_string();
+
+    #[cfg(not(feature = "utf16_strings"))]
+    self.encode_bytes(str.as_bytes());
+
+    #[cfg(feature = "utf16_strings")]
+    self.encode_slice(&str.encode_utf16().collect::<Vec<_>>());
+  }
+
+  #[cfg(feature = "std")]
+  fn encode_map<K: Serializer + Eq + Hash, V: Serializer, S>(&mut self, value: &HashMap<K, V, S>) {
+    let values = value
+      .iter()
+      .map(|it| MapEntry(it.0, it.1))
+      .collect::<Vec<_>>();
+
+    self.encode_slice(&values);
+  }
+
+  /// Same layout as [`Encoder::encode_map`] but in ascending key order,
+  /// so equal maps always encode to the same bytes.
+  #[cfg(feature = "std")]
+  fn encode_map_sorted<K: Serializer + Ord, V: Serializer, S>(&mut self, value: &HashMap<K, V, S>) {
+    let mut values = value
+      .iter()
+      .map(|it| MapEntry(it.0, it.1))
+      .collect::<Vec<_>>();
+
+    values.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+    self.encode_slice(&values);
+  }
+
+  fn encode_value<T: Serializer>(&mut self, value: &T) {
+    value.encode(self);
+  }
+
+  /// Writes `None` as `none` and `Some` as the bare value, without a tag.
+  ///
+  /// `Some(none)` would decode as `None`, it writes nothing and fails with [`EncoderError::ReservedSentinel`].
+  fn encode_sentinel_option<T: Serializer + PartialEq>(&mut self, value: &Option<T>, none: T) -> EncoderResult<()> {
+    match value {
+      Some(value) if *value == none => return Err(EncoderError::reserved_sentinel()),
+      Some(value) => self.encode_value(value),
+      None => self.encode_value(&none),
+    }
+
+    Ok(())
+  }
+
+  /// Writes what `f` encodes prefixed by its length in bytes, so a decoder can skip what it doesn't understand,
+  /// see [`Decoder::decode_block`](crate::decoder::Decoder::decode_block).
+  ///
+  /// `f` runs on this encoder so its settings carry over. The length has to be known before the block is written,
+  /// encoders that can't hold it back should pass [`EncoderError::Unsupported`] to [`Encoder::fail`].
+  fn encode_block(&mut self, f: impl FnOnce(&mut Self));
+
+  /// Records an error a [`Serializer`] can't return itself, the encoder reports it when it's finished.
+  ///
+  /// Ignored by default, encoders without a fallible `finish` have nowhere to report it.
+  fn fail(&mut self, err: EncoderError) {
+    let _ = err;
+  }
+
+  fn encode_enum_tag<E: EnumSerializer>(&mut self, value: &E) {
+    value.encode_tag(self);
+  }
+
+  fn encode_enum_payload<E: EnumSerializer>(&mut self, value: &E) {
+    value.encode_payload(self);
+  }
+}
+
+pub type EncoderResult<T> = core::result::Result<T, EncoderError>;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum EncoderError {
+  BufferOverflow {
+    capacity: usize,
+    required: usize,
+  },
+  BufferFull {
+    capacity: usize,
+  },
+  ReservedSentinel,
+  Unsupported {
+    operation: String,
+  },
+  #[cfg(feature = "std")]
+  Io {
+    kind: io::ErrorKind,
+    message: String,
+  },
+}
+
+impl EncoderError {
+  pub fn buffer_overflow(capacity: usize, required: usize) -> Self {
+    Self::BufferOverflow { capacity, required }
+  }
+
+  pub fn buffer_full(capacity: usize) -> Self {
+    Self::BufferFull { capacity }
+  }
+
+  pub fn reserved_sentinel() -> Self {
+    Self::ReservedSentinel
+  }
+
+  pub fn unsupported(operation: impl ToString) -> Self {
+    Self::Unsupported { operation: operation.to_string() }
+  }
+
+  #[cfg(feature = "std")]
+  pub fn io(err: io::Error) -> Self {
+    Self::Io { kind: err.kind(), message: err.to_string() }
+  }
+}
+
+impl Display for EncoderError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      EncoderError::BufferOverflow { capacity, required } => {
+        write!(f, "buffer with capacity of `{}` bytes is too small, `{}` bytes are required", capacity, required)
+      }
+      EncoderError::BufferFull { capacity } => {
+        write!(f, "buffer with capacity of `{}` bytes is full", capacity)
+      }
+      EncoderError::ReservedSentinel => {
+        write!(f, "`Some` holds the value reserved for `None`")
+      }
+      EncoderError::Unsupported { operation } => {
+        write!(f, "encoder doesn't support {}", operation)
+      }
+      #[cfg(feature = "std")]
+      EncoderError::Io { message, .. } => {
+        write!(f, "failed to write output: {}", message)
+      }
+    }
+  }
 }
 
+#[cfg(feature = "std")]
+impl Error for EncoderError {}
+
 pub struct ByteTracker {
   pub start: usize,
 }
 
 impl ByteTracker {
-  pub fn begin(bytes: &Vec<u8>) -> Self {
+  pub fn begin(bytes: &[u8]) -> Self {
     ByteTracker {
       start: bytes.len()
     }
   }
 
-  pub fn end(&self, new_bytes: &Vec<u8>) -> usize {
+  pub fn end(&self, new_bytes: &[u8]) -> usize {
     new_bytes.len() - self.start
   }
 }
 
-pub struct ByteEncoder {
+pub struct ByteEncoder<C: Codec = FixedCodec> {
   bytes: Vec<u8>,
   endian: ByteEndian,
+  varint_lengths: bool,
+  sorted_maps: bool,
+  error: Option<EncoderError>,
+  _codec: PhantomData<C>,
 }
 
 impl ByteEncoder {
   pub fn new(endian: ByteEndian) -> Self {
+    Self::with_codec(endian)
+  }
+
+  /// Appends to `bytes` instead of a new `Vec`, so a buffer can be reused across encodes
+  /// through [`ByteEncoder::into_bytes`].
+  pub fn with_buffer(bytes: Vec<u8>, endian: ByteEndian) -> Self {
+    Self { bytes, ..Self::new(endian) }
+  }
+}
+
+/// Appends `value` to `bytes`, reusing its capacity instead of allocating a new `Vec` like [`ToBytes::to_bytes`].
+///
+/// Panics like [`ToBytes::to_bytes`] if encoding fails.
+pub fn encode_into<T: Serializer + ?Sized>(value: &T, bytes: &mut Vec<u8>, endian: ByteEndian) {
+  let mut encoder = ByteEncoder::with_buffer(core::mem::take(bytes), endian);
+  value.encode(&mut encoder);
+
+  *bytes = encoder.finish_or_panic();
+}
+
+impl<C: Codec> ByteEncoder<C> {
+  pub fn with_codec(endian: ByteEndian) -> Self {
     Self {
       bytes: vec![],
       endian,
+      varint_lengths: false,
+      sorted_maps: false,
+      error: None,
+      _codec: PhantomData,
     }
   }
 
+  /// Reserves room for `capacity` bytes up front.
+  pub fn with_capacity(mut self, capacity: usize) -> Self {
+    self.bytes.reserve(capacity);
+    self
+  }
+
+  /// Writes the entries of every `HashMap` ordered by the bytes of their encoded keys,
+  /// making the output a pure function of the map contents.
+  pub fn with_sorted_maps(mut self) -> Self {
+    self.sorted_maps = true;
+    self
+  }
+
+  /// Writes every `usize`, which includes the length prefix of slices, strings and maps, as a LEB128 varint.
+  pub fn with_varint_lengths(mut self) -> Self {
+    self.varint_lengths = true;
+    self
+  }
+
   pub fn bytes(&self) -> &Vec<u8> {
     &self.bytes
   }
 
+  pub fn into_bytes(self) -> Vec<u8> {
+    self.bytes
+  }
+
+  /// Returns the encoded bytes, or the first error passed to [`Encoder::fail`].
+  pub fn finish(self) -> EncoderResult<Vec<u8>> {
+    match self.error {
+      Some(err) => Err(err),
+      None => Ok(self.bytes),
+    }
+  }
+
+  pub(crate) fn finish_or_panic(self) -> Vec<u8> {
+    match self.finish() {
+      Ok(bytes) => bytes,
+      Err(err) => panic!("failed to encode: {}", err),
+    }
+  }
+
   fn write<T: EndianValue<SIZE>, const SIZE: usize>(&mut self, value: T) {
-    self.bytes.write(&value.to_bytes_of(self.endian)).unwrap();
+    self.bytes.extend_from_slice(&value.to_bytes_of(self.endian));
+  }
+
+  fn write_int<T: Integer<SIZE>, const SIZE: usize>(&mut self, value: T) {
+    C::write(&mut self.bytes, self.endian, value);
   }
 }
 
-impl Encoder for ByteEncoder {
+impl<C: Codec> Encoder for ByteEncoder<C> {
+  fn endian(&self) -> ByteEndian { self.endian }
+  fn set_endian(&mut self, endian: ByteEndian) { self.endian = endian; }
+
+  fn fail(&mut self, err: EncoderError) {
+    self.error.get_or_insert(err);
+  }
+
+  fn encode_block(&mut self, f: impl FnOnce(&mut Self)) {
+    let start = self.bytes.len();
+    f(self);
+
+    let block = self.bytes.split_off(start);
+    self.encode_usize(block.len());
+    self.bytes.extend_from_slice(&block);
+  }
+
+  fn encode_u8(&mut self, value: u8) { self.write_int(value); }
+  fn encode_u16(&mut self, value: u16) { self.write_int(value); }
+  fn encode_u32(&mut self, value: u32) { self.write_int(value); }
+  fn encode_u64(&mut self, value: u64) { self.write_int(value); }
+  fn encode_u128(&mut self, value: u128) { self.write_int(value) }
+
+  fn encode_i8(&mut self, value: i8) { self.write_int(value); }
+  fn encode_i16(&mut self, value: i16) { self.write_int(value); }
+  fn encode_i32(&mut self, value: i32) { self.write_int(value); }
+  fn encode_i64(&mut self, value: i64) { self.write_int(value); }
+  fn encode_i128(&mut self, value: i128) { self.write_int(value) }
+
+  fn encode_f32(&mut self, value: f32) { self.write(value); }
+  fn encode_f64(&mut self, value: f64) { self.write(value); }
+
+  fn encode_usize(&mut self, value: usize) {
+    match self.varint_lengths {
+      true => self.encode_varint_u64(value as u64),
+      false => self.encode_u64(value as u64),
+    }
+  }
+
+  #[cfg(feature = "std")]
+  fn encode_map<K: Serializer + Eq + Hash, V: Serializer, S>(&mut self, value: &HashMap<K, V, S>) {
+    if !self.sorted_maps {
+      self.encode_slice(&value.iter().map(|it| MapEntry(it.0, it.1)).collect::<Vec<_>>());
+      return;
+    }
+
+    let mut entries = value
+      .iter()
+      .map(|(key, value)| {
+        let mut encoder = Self { bytes: vec![], error: None, ..*self };
+        key.encode(&mut encoder);
+
+        if let Some(err) = encoder.error {
+          self.fail(err);
+        }
+
+        (encoder.bytes, value)
+      })
+      .collect::<Vec<_>>();
+
+    entries.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+    self.encode_usize(entries.len());
+
+    for (key, value) in entries {
+      self.bytes.extend_from_slice(&key);
+      value.encode(self);
+    }
+  }
+
+  fn encode_slice<T: Serializer>(&mut self, value: &[T]) {
+    self.encode_usize(value.len());
+
+    // `usize` elements are varints as well with `varint_lengths`
+    if T::IS_PRIMITIVE && C::FIXED_WIDTH && self.endian.is_native() && !self.varint_lengths {
+      T::encode_native_slice(value, &mut self.bytes);
+    } else {
+      for value in value {
+        value.encode(self);
+      }
+    }
+  }
+
+  fn encode_bytes(&mut self, value: &[u8]) {
+    self.encode_usize(value.len());
+
+    // other codecs are free to write a `u8` as something else than itself
+    if C::FIXED_WIDTH {
+      self.bytes.extend_from_slice(value);
+    } else {
+      for &value in value {
+        self.encode_u8(value);
+      }
+    }
+  }
+
+  fn encode_fixed_bytes(&mut self, value: &[u8], len: usize) {
+    if C::FIXED_WIDTH {
+      let value = &value[..value.len().min(len)];
+
+      self.bytes.extend_from_slice(value);
+      self.bytes.resize(self.bytes.len() + len - value.len(), 0);
+    } else {
+      for idx in 0..len {
+        self.encode_u8(value.get(idx).copied().unwrap_or(0));
+      }
+    }
+  }
+}
+
+/// Encodes into a caller provided buffer (a stack array, a memory mapped region, ...) instead of a `Vec`.
+///
+/// Writing past the end of the buffer doesn't panic, the remaining bytes are only counted
+/// and [`SliceEncoder::finish`] reports how many would have been required.
+pub struct SliceEncoder<'a> {
+  bytes: &'a mut [u8],
+  endian: ByteEndian,
+  required: usize,
+  error: Option<EncoderError>,
+}
+
+impl<'a> SliceEncoder<'a> {
+  pub fn new(bytes: &'a mut [u8], endian: ByteEndian) -> Self {
+    Self { bytes, endian, required: 0, error: None }
+  }
+
+  /// Returns the amount of bytes written.
+  pub fn finish(self) -> EncoderResult<usize> {
+    if let Some(err) = self.error {
+      return Err(err);
+    }
+
+    if self.required > self.bytes.len() {
+      return Err(EncoderError::buffer_overflow(self.bytes.len(), self.required));
+    }
+
+    Ok(self.required)
+  }
+
+  fn write<T: EndianValue<SIZE>, const SIZE: usize>(&mut self, value: T) {
+    let start = self.required;
+    self.required += SIZE;
+
+    if let Some(bytes) = self.bytes.get_mut(start..self.required) {
+      bytes.copy_from_slice(&value.to_bytes_of(self.endian));
+    }
+  }
+}
+
+impl<'a> Encoder for SliceEncoder<'a> {
+  fn endian(&self) -> ByteEndian { self.endian }
+  fn set_endian(&mut self, endian: ByteEndian) { self.endian = endian; }
+
+  fn fail(&mut self, err: EncoderError) {
+    self.error.get_or_insert(err);
+  }
+
+  fn encode_block(&mut self, f: impl FnOnce(&mut Self)) {
+    // lengths always take up 8 bytes here, the prefix is filled in once the block is written
+    let start = self.required;
+    self.encode_usize(0);
+
+    let block = self.required;
+    f(self);
+
+    let end = self.required;
+    self.required = start;
+    self.encode_usize(end - block);
+    self.required = end;
+  }
+
   fn encode_u8(&mut self, value: u8) { self.write(value); }
   fn encode_u16(&mut self, value: u16) { self.write(value); }
   fn encode_u32(&mut self, value: u32) { self.write(value); }
@@ -89,104 +586,568 @@ impl Encoder for ByteEncoder {
   fn encode_f32(&mut self, value: f32) { self.write(value); }
   fn encode_f64(&mut self, value: f64) { self.write(value); }
 
-  fn encode_slice<T: Serializer>(&mut self, value: &[T]) {
+  fn encode_bytes(&mut self, value: &[u8]) {
     self.encode_usize(value.len());
 
-    for value in value {
-      value.encode(self);
+    let start = self.required;
+    self.required += value.len();
+
+    if let Some(bytes) = self.bytes.get_mut(start..self.required) {
+      bytes.copy_from_slice(value);
     }
   }
+}
 
-  fn encode_string(&mut self, value: impl ToString) {
-    let str = value.to_string();
-    let vec = str.encode_utf16().collect::<Vec<_>>();
+/// Encodes into a bounded queue of bytes that a consumer drains with [`RingEncoder::read`],
+/// so a producer that outpaces it has to wait instead of growing the buffer.
+///
+/// Values should go through [`RingEncoder::try_encode`], bytes that don't fit are dropped otherwise.
+pub struct RingEncoder {
+  bytes: VecDeque<u8>,
+  capacity: usize,
+  endian: ByteEndian,
+  full: bool,
+  error: Option<EncoderError>,
+}
 
-    self.encode_slice(&vec);
+impl RingEncoder {
+  pub fn new(capacity: usize, endian: ByteEndian) -> Self {
+    Self { bytes: VecDeque::with_capacity(capacity), capacity, endian, full: false, error: None }
   }
 
-  fn encode_map<K: Serializer + Eq + Hash, V: Serializer>(&mut self, value: &HashMap<K, V>) {
-    let values = value
-      .iter()
-      .map(|it| MapEntry(it.0, it.1))
-      .collect::<Vec<_>>();
+  pub fn capacity(&self) -> usize { self.capacity }
 
-    self.encode_slice(&values);
-  }
+  pub fn len(&self) -> usize { self.bytes.len() }
 
-  fn encode_value<T: Serializer>(&mut self, value: &T) {
+  pub fn is_empty(&self) -> bool { self.bytes.is_empty() }
+
+  /// Encodes all of `value` or, if it doesn't fit in the free space, nothing and returns [`EncoderError::BufferFull`].
+  pub fn try_encode<T: Serializer>(&mut self, value: &T) -> EncoderResult<()> {
+    let start = self.bytes.len();
+
+    self.full = false;
     value.encode(self);
+
+    if let Some(err) = self.error.take() {
+      self.bytes.truncate(start);
+      return Err(err);
+    }
+
+    if self.full {
+      self.bytes.truncate(start);
+      return Err(EncoderError::buffer_full(self.capacity));
+    }
+
+    Ok(())
+  }
+
+  /// Moves the oldest bytes into `out`, returns how many were moved.
+  pub fn read(&mut self, out: &mut [u8]) -> usize {
+    let len = out.len().min(self.bytes.len());
+
+    for (out, byte) in out.iter_mut().zip(self.bytes.drain(..len)) {
+      *out = byte;
+    }
+
+    len
+  }
+
+  fn write<T: EndianValue<SIZE>, const SIZE: usize>(&mut self, value: T) {
+    if self.full || self.bytes.len() + SIZE > self.capacity {
+      self.full = true;
+      return;
+    }
+
+    self.bytes.extend(value.to_bytes_of(self.endian));
+  }
+}
+
+impl Encoder for RingEncoder {
+  fn endian(&self) -> ByteEndian { self.endian }
+  fn set_endian(&mut self, endian: ByteEndian) { self.endian = endian; }
+
+  fn fail(&mut self, err: EncoderError) {
+    self.error.get_or_insert(err);
+  }
+
+  fn encode_block(&mut self, f: impl FnOnce(&mut Self)) {
+    let start = self.bytes.len();
+    f(self);
+
+    if self.full {
+      return;
+    }
+
+    let block = self.bytes.drain(start..).collect::<Vec<_>>();
+    self.encode_usize(block.len());
+
+    if self.full || self.bytes.len() + block.len() > self.capacity {
+      self.full = true;
+      return;
+    }
+
+    self.bytes.extend(block);
+  }
+
+  fn encode_u8(&mut self, value: u8) { self.write(value); }
+  fn encode_u16(&mut self, value: u16) { self.write(value); }
+  fn encode_u32(&mut self, value: u32) { self.write(value); }
+  fn encode_u64(&mut self, value: u64) { self.write(value); }
+  fn encode_u128(&mut self, value: u128) { self.write(value) }
+
+  fn encode_i8(&mut self, value: i8) { self.write(value); }
+  fn encode_i16(&mut self, value: i16) { self.write(value); }
+  fn encode_i32(&mut self, value: i32) { self.write(value); }
+  fn encode_i64(&mut self, value: i64) { self.write(value); }
+  fn encode_i128(&mut self, value: i128) { self.write(value) }
+
+  fn encode_f32(&mut self, value: f32) { self.write(value); }
+  fn encode_f64(&mut self, value: f64) { self.write(value); }
+}
+
+/// Writes every primitive straight to `writer`, wrap it in a `BufWriter` if it's unbuffered.
+///
+/// The first error stops all further writes and is returned by [`WriteEncoder::finish`].
+#[cfg(feature = "std")]
+pub struct WriteEncoder<W: Write> {
+  writer: W,
+  endian: ByteEndian,
+  error: Option<EncoderError>,
+  /// Holds back the bytes of a block until its length is known, see [`Encoder::encode_block`].
+  block: Option<Vec<u8>>,
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> WriteEncoder<W> {
+  pub fn new(writer: W, endian: ByteEndian) -> Self {
+    Self { writer, endian, error: None, block: None }
+  }
+
+  /// Flushes and returns the writer, or the first error any write ran into.
+  pub fn finish(mut self) -> EncoderResult<W> {
+    if let Some(err) = self.error {
+      return Err(err);
+    }
+
+    self.writer.flush().map_err(EncoderError::io)?;
+
+    Ok(self.writer)
+  }
+
+  fn write<T: EndianValue<SIZE>, const SIZE: usize>(&mut self, value: T) {
+    self.write_all(&value.to_bytes_of(self.endian));
+  }
+
+  fn write_all(&mut self, bytes: &[u8]) {
+    if self.error.is_some() {
+      return;
+    }
+
+    if let Some(block) = &mut self.block {
+      block.extend_from_slice(bytes);
+    } else if let Err(err) = self.writer.write_all(bytes) {
+      self.error = Some(EncoderError::io(err));
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl<W: Write> Encoder for WriteEncoder<W> {
+  fn endian(&self) -> ByteEndian { self.endian }
+  fn set_endian(&mut self, endian: ByteEndian) { self.endian = endian; }
+
+  fn fail(&mut self, err: EncoderError) {
+    self.error.get_or_insert(err);
+  }
+
+  fn encode_block(&mut self, f: impl FnOnce(&mut Self)) {
+    let outer = self.block.replace(vec![]);
+    f(self);
+
+    let block = core::mem::replace(&mut self.block, outer).unwrap_or_default();
+    self.encode_usize(block.len());
+    self.write_all(&block);
+  }
+
+  fn encode_u8(&mut self, value: u8) { self.write(value); }
+  fn encode_u16(&mut self, value: u16) { self.write(value); }
+  fn encode_u32(&mut self, value: u32) { self.write(value); }
+  fn encode_u64(&mut self, value: u64) { self.write(value); }
+  fn encode_u128(&mut self, value: u128) { self.write(value) }
+
+  fn encode_i8(&mut self, value: i8) { self.write(value); }
+  fn encode_i16(&mut self, value: i16) { self.write(value); }
+  fn encode_i32(&mut self, value: i32) { self.write(value); }
+  fn encode_i64(&mut self, value: i64) { self.write(value); }
+  fn encode_i128(&mut self, value: i128) { self.write(value) }
+
+  fn encode_f32(&mut self, value: f32) { self.write(value); }
+  fn encode_f64(&mut self, value: f64) { self.write(value); }
+
+  fn encode_bytes(&mut self, value: &[u8]) {
+    self.encode_usize(value.len());
+    self.write_all(value);
   }
 }
 
 pub trait ToBytes: Serializer {
+  /// Panics if anything passed an error to [`Encoder::fail`], since the bytes written so far can't be decoded,
+  /// use `try_to_bytes` to handle it instead.
   fn to_bytes(&self, endian: ByteEndian) -> Vec<u8> {
     let mut encoder = ByteEncoder::new(endian);
     self.encode(&mut encoder);
 
-    encoder.bytes
+    encoder.finish_or_panic()
+  }
+
+  /// Like `to_bytes`, but returns the error passed to [`Encoder::fail`] instead of panicking.
+  fn try_to_bytes(&self, endian: ByteEndian) -> EncoderResult<Vec<u8>> {
+    let mut encoder = ByteEncoder::new(endian);
+    self.encode(&mut encoder);
+
+    encoder.finish()
+  }
+
+  /// Like `to_bytes`, but allocates the output once up front from the [`SizeHint`].
+  fn to_bytes_sized(&self, endian: ByteEndian) -> Vec<u8> where Self: SizeHint {
+    let mut encoder = ByteEncoder::new(endian).with_capacity(self.encoded_size());
+    self.encode(&mut encoder);
+
+    encoder.finish_or_panic()
+  }
+
+  /// Encodes into `bytes` and returns the amount of bytes written, see [`SliceEncoder`].
+  fn to_slice(&self, bytes: &mut [u8], endian: ByteEndian) -> EncoderResult<usize> {
+    let mut encoder = SliceEncoder::new(bytes, endian);
+    self.encode(&mut encoder);
+
+    encoder.finish()
+  }
+
+  /// Encodes straight into `writer`, see [`WriteEncoder`].
+  #[cfg(feature = "std")]
+  fn to_writer<W: Write>(&self, writer: W, endian: ByteEndian) -> EncoderResult<W> {
+    let mut encoder = WriteEncoder::new(writer, endian);
+    self.encode(&mut encoder);
+
+    encoder.finish()
   }
 }
 
 impl<T: Serializer> ToBytes for T {}
 
 pub trait Serializer {
+  /// Set for integers and floats whose in-memory layout matches their wire format,
+  /// [`ByteEncoder`] copies slices of those straight into native-endian output.
+  #[doc(hidden)]
+  const IS_PRIMITIVE: bool = false;
+
   fn encode(&self, encoder: &mut impl Encoder);
+
+  /// Appends every value in native endianness, only called when `IS_PRIMITIVE` is set.
+  ///
+  /// Encodes them one at a time unless overridden, setting `IS_PRIMITIVE` on anything else only makes it slower.
+  #[doc(hidden)]
+  fn encode_native_slice(values: &[Self], bytes: &mut Vec<u8>) where Self: Sized {
+    let mut encoder = ByteEncoder::with_buffer(core::mem::take(bytes), ByteEndian::Native);
+
+    for value in values {
+      value.encode(&mut encoder);
+    }
+
+    *bytes = encoder.into_bytes();
+  }
+
+  /// Encodes a length prefixed run of values, `u8` sends it through [`Encoder::encode_bytes`] instead.
+  #[doc(hidden)]
+  fn encode_many(values: &[Self], encoder: &mut impl Encoder) where Self: Sized {
+    encoder.encode_slice(values);
+  }
+}
+
+/// Split codec for enums, lets the discriminant and the variant data be written to different encoders.
+///
+/// `encode` for a derived enum is always `encode_tag` followed by `encode_payload`.
+pub trait EnumSerializer: Serializer {
+  type Tag: Serializer;
+
+  fn tag(&self) -> Self::Tag;
+
+  fn encode_tag(&self, encoder: &mut impl Encoder) {
+    encoder.encode_value(&self.tag());
+  }
+
+  fn encode_payload(&self, encoder: &mut impl Encoder);
 }
 
-impl Serializer for &str {
+/// Same as the value behind it, lets borrowed views derive `Serializer`.
+impl<T: Serializer + ?Sized> Serializer for &T {
   fn encode(&self, encoder: &mut impl Encoder) {
-    encoder.encode_string(self)
+    (**self).encode(encoder)
   }
 }
 
-impl Serializer for String {
+impl Serializer for str {
   fn encode(&self, encoder: &mut impl Encoder) {
     encoder.encode_string(self)
   }
 }
 
-impl<T: Serializer> Serializer for &[T] {
+impl Serializer for String {
   fn encode(&self, encoder: &mut impl Encoder) {
-    encoder.encode_slice(self)
+    encoder.encode_string(self)
   }
 }
 
 impl<T: Serializer> Serializer for [T] {
   fn encode(&self, encoder: &mut impl Encoder) {
-    encoder.encode_slice(self)
+    T::encode_many(self, encoder)
   }
 }
 
+/// Written without a length prefix, `N` is known on both sides.
 impl<T: Serializer, const N: usize> Serializer for [T; N] {
   fn encode(&self, encoder: &mut impl Encoder) {
-    encoder.encode_slice(self)
+    for value in self {
+      encoder.encode_value(value);
+    }
   }
 }
 
 impl<T: Serializer> Serializer for Vec<T> {
   fn encode(&self, encoder: &mut impl Encoder) {
-    encoder.encode_slice(self);
+    T::encode_many(self, encoder);
   }
 }
 
-impl<K: Serializer + Eq + Hash, V: Serializer> Serializer for HashMap<K, V> {
+#[cfg(feature = "std")]
+impl<K: Serializer + Eq + Hash, V: Serializer, S> Serializer for HashMap<K, V, S> {
   fn encode(&self, encoder: &mut impl Encoder) {
     encoder.encode_map(self);
   }
 }
 
+/// Same layout as a slice of the values, in iteration order.
+#[cfg(feature = "std")]
+impl<T: Serializer + Eq + Hash, S> Serializer for HashSet<T, S> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_slice(&self.iter().collect::<Vec<_>>());
+  }
+}
+
+/// Same layout as a `HashMap`, always in ascending key order.
+impl<K: Serializer, V: Serializer> Serializer for BTreeMap<K, V> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_slice(&self.iter().map(|it| MapEntry(it.0, it.1)).collect::<Vec<_>>());
+  }
+}
+
+/// Same layout as a slice of the values, in ascending order.
+impl<T: Serializer> Serializer for BTreeSet<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_slice(&self.iter().collect::<Vec<_>>());
+  }
+}
+
+/// Same layout as a slice of the values, front to back.
+impl<T: Serializer> Serializer for VecDeque<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_slice(&self.iter().collect::<Vec<_>>());
+  }
+}
+
+/// Same layout as a slice of the values, front to back.
+impl<T: Serializer> Serializer for LinkedList<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_slice(&self.iter().collect::<Vec<_>>());
+  }
+}
+
+/// Same layout as a slice of the values, in the heap's internal order.
+impl<T: Serializer> Serializer for BinaryHeap<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_slice(&self.iter().collect::<Vec<_>>());
+  }
+}
+
+/// Written as a `0` (`None`) or `1` (`Some`) byte, followed by the value if there is one.
+impl<T: Serializer> Serializer for Option<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    match self {
+      Some(value) => {
+        encoder.encode_u8(1);
+        encoder.encode_value(value);
+      }
+      None => encoder.encode_u8(0),
+    }
+  }
+}
+
+/// Written as a `0` (`Ok`) or `1` (`Err`) byte followed by the value.
+impl<T: Serializer, E: Serializer> Serializer for Result<T, E> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    match self {
+      Ok(value) => {
+        encoder.encode_u8(0);
+        encoder.encode_value(value);
+      }
+      Err(err) => {
+        encoder.encode_u8(1);
+        encoder.encode_value(err);
+      }
+    }
+  }
+}
+
+/// Written as `as_secs` followed by `subsec_nanos`, which is always below one second.
+impl Serializer for Duration {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u64(self.as_secs());
+    encoder.encode_u32(self.subsec_nanos());
+  }
+}
+
+impl<T: Serializer> Serializer for Range<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_value(&self.start);
+    encoder.encode_value(&self.end);
+  }
+}
+
+/// Only the bounds are written, a range that was iterated to exhaustion decodes as a fresh one.
+impl<T: Serializer> Serializer for RangeInclusive<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_value(self.start());
+    encoder.encode_value(self.end());
+  }
+}
+
+#[cfg(feature = "std")]
+impl Serializer for Ipv4Addr {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_value(&self.octets());
+  }
+}
+
+#[cfg(feature = "std")]
+impl Serializer for Ipv6Addr {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_value(&self.octets());
+  }
+}
+
+/// `0` for v4 and `1` for v6, followed by the address.
+#[cfg(feature = "std")]
+impl Serializer for IpAddr {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    match self {
+      IpAddr::V4(addr) => {
+        encoder.encode_u8(0);
+        encoder.encode_value(addr);
+      }
+      IpAddr::V6(addr) => {
+        encoder.encode_u8(1);
+        encoder.encode_value(addr);
+      }
+    }
+  }
+}
+
+/// The port is always big endian, same as on the network.
+#[cfg(feature = "std")]
+impl Serializer for SocketAddrV4 {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_value(self.ip());
+    encoder.with_endian(ByteEndian::Big, |encoder| encoder.encode_u16(self.port()));
+  }
+}
+
+/// The port, flow info and scope id are always big endian, same as on the network.
+#[cfg(feature = "std")]
+impl Serializer for SocketAddrV6 {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_value(self.ip());
+    encoder.with_endian(ByteEndian::Big, |encoder| {
+      encoder.encode_u16(self.port());
+      encoder.encode_u32(self.flowinfo());
+      encoder.encode_u32(self.scope_id());
+    });
+  }
+}
+
+/// `0` for v4 and `1` for v6, followed by the address.
+#[cfg(feature = "std")]
+impl Serializer for SocketAddr {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    match self {
+      SocketAddr::V4(addr) => {
+        encoder.encode_u8(0);
+        encoder.encode_value(addr);
+      }
+      SocketAddr::V6(addr) => {
+        encoder.encode_u8(1);
+        encoder.encode_value(addr);
+      }
+    }
+  }
+}
+
+/// Written as the value it points to, borrowed or owned.
+impl<B: Serializer + ToOwned + ?Sized> Serializer for Cow<'_, B> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    (**self).encode(encoder);
+  }
+}
+
+macro_rules! impl_serializer_pointer {
+  ($($pointer:ident),+) => {
+    $(/// Written as the value itself, shared values are written again for every pointer.
+    impl<T: Serializer> Serializer for $pointer<T> {
+      fn encode(&self, encoder: &mut impl Encoder) {
+        encoder.encode_value(self.as_ref());
+      }
+    }
+
+    impl Serializer for $pointer<str> {
+      fn encode(&self, encoder: &mut impl Encoder) {
+        encoder.encode_string(&**self)
+      }
+    })+
+  };
+}
+
+impl_serializer_pointer!(Box, Rc, Arc);
+
+/// Weak links can't own their data, so they are always written as absent (a single `0` byte)
+/// and have to be rebuilt from the strong side after decoding.
+impl<T: ?Sized> Serializer for rc::Weak<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u8(0);
+  }
+}
+
+/// Same as the `rc::Weak` impl, weak links are not preserved.
+impl<T: ?Sized> Serializer for sync::Weak<T> {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u8(0);
+  }
+}
+
 macro_rules! impl_serializer_tuple {
-  ($($name:ident),+) => {
-    impl <$($name: Serializer),+> Serializer for ($($name),+) {
+  ($($name:ident),*) => {
+    impl <$($name: Serializer),*> Serializer for ($($name,)*) {
       #[allow(non_snake_case)]
       fn encode(&self, encoder: &mut impl Encoder) {
-        let ($($name),+) = self;
-        $($name.encode(encoder);)+
+        let ($($name,)*) = self;
+        $($name.encode(encoder);)*
       }
     }
   };
 }
 
+// `()` writes nothing
+impl_serializer_tuple!();
+impl_serializer_tuple!(A);
 impl_serializer_tuple!(A, B);
 impl_serializer_tuple!(A, B, C);
 impl_serializer_tuple!(A, B, C, D);
@@ -195,6 +1156,13 @@ impl_serializer_tuple!(A, B, C, D, E, F);
 impl_serializer_tuple!(A, B, C, D, E, F, G);
 impl_serializer_tuple!(A, B, C, D, E, F, G, J);
 impl_serializer_tuple!(A, B, C, D, E, F, G, J, K);
+impl_serializer_tuple!(A, B, C, D, E, F, G, J, K, L);
+impl_serializer_tuple!(A, B, C, D, E, F, G, J, K, L, M);
+impl_serializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N);
+impl_serializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O);
+impl_serializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P);
+impl_serializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q);
+impl_serializer_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q, R);
 
 macro_rules! impl_serializer {
   ($(($type:ty, $encode:ident)),+ $(,)?) => {
@@ -206,9 +1174,101 @@ macro_rules! impl_serializer {
   };
 }
 
-impl_serializer!(
-  (u8, encode_u8), (u16, encode_u16), (u32, encode_u32), (u64, encode_u64), (u128, encode_u128), (usize, encode_usize),
+macro_rules! impl_serializer_primitive {
+  ($(($type:ty, $encode:ident)),+ $(,)?) => {
+    $(impl Serializer for $type {
+      // `usize` and `isize` are always 8 bytes on the wire
+      const IS_PRIMITIVE: bool = size_of::<$type>() == <$type as FixedSize>::WIRE_SIZE;
+
+      fn encode(&self, encoder: &mut impl Encoder) {
+        encoder.$encode(*self);
+      }
+
+      fn encode_native_slice(values: &[Self], bytes: &mut Vec<u8>) {
+        // SAFETY: integers and floats have no padding, their memory is exactly their native-endian bytes
+        let raw = unsafe { core::slice::from_raw_parts(values.as_ptr().cast::<u8>(), size_of_val(values)) };
+
+        bytes.extend_from_slice(raw);
+      }
+    })+
+  };
+}
+
+impl Serializer for u8 {
+  const IS_PRIMITIVE: bool = true;
+
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u8(*self);
+  }
+
+  fn encode_native_slice(values: &[Self], bytes: &mut Vec<u8>) {
+    bytes.extend_from_slice(values);
+  }
+
+  fn encode_many(values: &[Self], encoder: &mut impl Encoder) {
+    encoder.encode_bytes(values);
+  }
+}
+
+impl_serializer_primitive!(
+  (u16, encode_u16), (u32, encode_u32), (u64, encode_u64), (u128, encode_u128), (usize, encode_usize),
   (i8, encode_i8), (i16, encode_i16), (i32, encode_i32), (i64, encode_i64), (i128, encode_i128), (isize, encode_isize),
-  (f32, encode_f32), (f64, encode_f64), (bool, encode_bool)
+  (f32, encode_f32), (f64, encode_f64)
+);
+
+#[cfg(feature = "half")]
+impl_serializer_primitive!((f16, encode_f16));
+
+impl_serializer!((bool, encode_bool));
+
+/// Writes nothing.
+impl<T: ?Sized> Serializer for PhantomData<T> {
+  fn encode(&self, _encoder: &mut impl Encoder) {}
+}
+
+/// Writes nothing.
+impl Serializer for PhantomPinned {
+  fn encode(&self, _encoder: &mut impl Encoder) {}
+}
+
+macro_rules! impl_serializer_non_zero {
+  ($(($type:ty, $encode:ident)),+ $(,)?) => {
+    $(impl Serializer for $type {
+      fn encode(&self, encoder: &mut impl Encoder) {
+        encoder.$encode(self.get());
+      }
+    })+
+  };
+}
+
+impl_serializer_non_zero!(
+  (NonZeroU8, encode_u8), (NonZeroU16, encode_u16), (NonZeroU32, encode_u32),
+  (NonZeroU64, encode_u64), (NonZeroU128, encode_u128), (NonZeroUsize, encode_usize),
+  (NonZeroI8, encode_i8), (NonZeroI16, encode_i16), (NonZeroI32, encode_i32),
+  (NonZeroI64, encode_i64), (NonZeroI128, encode_i128), (NonZeroIsize, encode_isize)
 );
 
+macro_rules! impl_serializer_atomic {
+  ($(($type:ty, $encode:ident)),+ $(,)?) => {
+    $(/// A `Relaxed` snapshot of the value, written the same as the plain type.
+    impl Serializer for $type {
+      fn encode(&self, encoder: &mut impl Encoder) {
+        encoder.$encode(self.load(Ordering::Relaxed));
+      }
+    })+
+  };
+}
+
+impl_serializer_atomic!(
+  (AtomicU8, encode_u8), (AtomicU16, encode_u16), (AtomicU32, encode_u32), (AtomicU64, encode_u64), (AtomicUsize, encode_usize),
+  (AtomicI8, encode_i8), (AtomicI16, encode_i16), (AtomicI32, encode_i32), (AtomicI64, encode_i64), (AtomicIsize, encode_isize),
+  (AtomicBool, encode_bool)
+);
+
+/// Its scalar value as a `u32`.
+impl Serializer for char {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u32(*self as u32);
+  }
+}
+
@@ -0,0 +1,37 @@
+//! Self-delimiting messages for streams, every value is written as its encoded length (a `u32`) followed by the bytes.
+
+use std::io::{self, Read, Write};
+
+use crate::common::{ByteEndian, EndianValue};
+use crate::decoder::{DecoderError, DecoderResult, Deserializer, FromBytes};
+use crate::encoder::{EncoderError, EncoderResult, Serializer, ToBytes};
+
+/// Encodes `value` and writes it as one frame, values that encode to more than `u32::MAX` bytes are rejected.
+pub fn write_framed<T: Serializer, W: Write>(value: &T, mut writer: W, endian: ByteEndian) -> EncoderResult<()> {
+  let bytes = value.try_to_bytes(endian)?;
+  let len = u32::try_from(bytes.len()).map_err(|_| EncoderError::buffer_overflow(u32::MAX as usize, bytes.len()))?;
+
+  writer.write_all(&len.to_bytes_of(endian)).map_err(EncoderError::io)?;
+  writer.write_all(&bytes).map_err(EncoderError::io)
+}
+
+/// Reads one frame and decodes it, the value has to take up the whole frame.
+pub fn read_framed<T: Deserializer, R: Read>(mut reader: R, endian: ByteEndian) -> DecoderResult<T> {
+  let mut len = [0; 4];
+  reader.read_exact(&mut len).map_err(|err| match err.kind() {
+    io::ErrorKind::UnexpectedEof => DecoderError::not_enough_bytes("u32", 0),
+    _ => DecoderError::io(err),
+  })?;
+
+  let len = u32::from_bytes_of(endian, len) as usize;
+
+  // `take` grows the buffer as bytes arrive instead of trusting `len` up front
+  let mut bytes = Vec::new();
+  reader.take(len as u64).read_to_end(&mut bytes).map_err(DecoderError::io)?;
+
+  if bytes.len() < len {
+    return Err(DecoderError::not_enough_bytes(format!("[u8; {}]", len), 4 + bytes.len()));
+  }
+
+  T::from_bytes_exact(&bytes, endian)
+}
\ No newline at end of file
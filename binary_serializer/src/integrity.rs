@@ -0,0 +1,87 @@
+//! Detects accidental corruption, values are followed by a CRC32 of their encoded bytes.
+//!
+//! Nothing stops deliberate tampering, the checksum is trivial to recompute.
+
+use alloc::vec::Vec;
+
+use crate::common::{ByteEndian, EndianValue};
+use crate::decoder::{ByteDecoder, DecoderError, DecoderResult, Deserializer};
+use crate::encoder::{ByteEncoder, Encoder, EncoderResult, Serializer};
+
+const CHECKSUM_SIZE: usize = 4;
+
+/// Wraps a [`ByteEncoder`], [`ChecksummedEncoder::finish`] appends the checksum of everything encoded through it.
+pub struct ChecksummedEncoder {
+  encoder: ByteEncoder,
+}
+
+impl ChecksummedEncoder {
+  pub fn new(endian: ByteEndian) -> Self {
+    Self { encoder: ByteEncoder::new(endian) }
+  }
+
+  pub fn encoder(&mut self) -> &mut ByteEncoder {
+    &mut self.encoder
+  }
+
+  pub fn encode_value<T: Serializer + ?Sized>(&mut self, value: &T) {
+    value.encode(&mut self.encoder);
+  }
+
+  /// The encoded bytes followed by their checksum, a `u32` in the encoder's endianness.
+  pub fn finish(self) -> EncoderResult<Vec<u8>> {
+    let endian = self.encoder.endian();
+    let mut bytes = self.encoder.finish()?;
+    let checksum = crc32fast::hash(&bytes);
+
+    bytes.extend_from_slice(&checksum.to_bytes_of(endian));
+    Ok(bytes)
+  }
+}
+
+/// Verifies the checksum up front, values are then decoded from the payload through [`ChecksummedDecoder::decoder`].
+pub struct ChecksummedDecoder<'a> {
+  decoder: ByteDecoder<'a>,
+}
+
+impl<'a> ChecksummedDecoder<'a> {
+  /// Fails with [`DecoderError::ChecksumMismatch`] unless `bytes` end in the checksum of what comes before.
+  pub fn new(bytes: &'a [u8], endian: ByteEndian) -> DecoderResult<Self> {
+    let split = bytes.len()
+      .checked_sub(CHECKSUM_SIZE)
+      .ok_or_else(|| DecoderError::not_enough_bytes("u32", bytes.len()))?;
+    let (payload, checksum) = bytes.split_at(split);
+
+    let expected = u32::from_bytes_of(endian, checksum.try_into().unwrap());
+    let actual = crc32fast::hash(payload);
+
+    if expected != actual {
+      return Err(DecoderError::checksum_mismatch(expected, actual));
+    }
+
+    Ok(Self { decoder: ByteDecoder::new(payload, endian) })
+  }
+
+  pub fn decoder(&mut self) -> &mut ByteDecoder<'a> {
+    &mut self.decoder
+  }
+}
+
+/// Like [`ToBytes::try_to_bytes`](crate::encoder::ToBytes::try_to_bytes), followed by the checksum.
+pub fn to_bytes_checked<T: Serializer + ?Sized>(value: &T, endian: ByteEndian) -> EncoderResult<Vec<u8>> {
+  let mut encoder = ChecksummedEncoder::new(endian);
+  encoder.encode_value(value);
+
+  encoder.finish()
+}
+
+/// Like [`FromBytes::from_bytes_exact`](crate::decoder::FromBytes::from_bytes_exact) on the payload once its checksum is verified.
+pub fn from_bytes_checked<T: Deserializer>(bytes: &[u8], endian: ByteEndian) -> DecoderResult<T> {
+  let mut decoder = ChecksummedDecoder::new(bytes, endian)?;
+  let value = T::decode(decoder.decoder())?;
+
+  match decoder.decoder().remaining() {
+    0 => Ok(value),
+    remaining => Err(DecoderError::trailing_bytes(remaining)),
+  }
+}
\ No newline at end of file
@@ -1,16 +1,46 @@
 #![allow(unused)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 use crate::common::*;
 use crate::decoder::*;
 use crate::encoder::*;
 
+pub mod codec;
 pub mod common;
 pub mod encoder;
 pub mod decoder;
+#[cfg(feature = "std")]
+pub mod framing;
+#[cfg(feature = "checksum")]
+pub mod integrity;
+#[cfg(feature = "compress")]
+pub mod compress;
+#[cfg(feature = "std")]
+pub mod migrate;
+#[cfg(feature = "std")]
+pub mod rpc;
+pub mod size_hint;
+#[cfg(feature = "std")]
+pub mod symbol;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transcode;
+
+/// Paths used by the derive macros, which can't rely on `Vec` and `Box` being in scope without `std`.
+#[doc(hidden)]
+pub mod __private {
+  pub use alloc::borrow::Cow;
+  pub use alloc::boxed::Box;
+  pub use alloc::vec::Vec;
+}
 
 #[cfg(feature = "prelude")]
 pub mod prelude {
+  pub use crate::codec::*;
   pub use crate::common::*;
   pub use crate::decoder::*;
   pub use crate::encoder::*;
+  pub use crate::size_hint::*;
 }
\ No newline at end of file
@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::common::ByteEndian;
+use crate::decoder::{ByteDecoder, Decoder, DecoderError, DecoderResult, Deserializer, FromBytes};
+use crate::encoder::{ByteEncoder, Encoder, EncoderResult, Serializer, ToBytes};
+
+/// Decodes the payload of one version and returns it re-encoded as the next one.
+pub type MigrationFn = Box<dyn Fn(&[u8], ByteEndian) -> DecoderResult<Vec<u8>>>;
+
+/// Upgrades values written by older versions of `T` to the current one.
+///
+/// Values are written as a `u32` version followed by the payload of that version,
+/// `from_bytes_migrated` runs the registered migrations one after another until it reaches the current version.
+pub struct MigrationRegistry<T> {
+  version: u32,
+  migrations: HashMap<u32, (u32, MigrationFn)>,
+  _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Serializer + Deserializer> MigrationRegistry<T> {
+  pub fn new(version: u32) -> Self {
+    Self { version, migrations: HashMap::new(), _marker: PhantomData }
+  }
+
+  pub fn version(&self) -> u32 {
+    self.version
+  }
+
+  /// Registers the migration from version `from` to `to`, replacing any previous one from `from`.
+  ///
+  /// Panics unless `from < to`, so a chain of migrations always ends.
+  pub fn register<From, To>(mut self, from: u32, to: u32, migrate: impl Fn(From) -> To + 'static) -> Self
+    where From: Deserializer,
+          To: Serializer {
+    assert!(from < to, "migration from version `{}` to `{}` doesn't go forward", from, to);
+
+    let migrate: MigrationFn = Box::new(move |bytes, endian| {
+      migrate(From::from_bytes(bytes, endian)?).try_to_bytes(endian).map_err(DecoderError::custom)
+    });
+    self.migrations.insert(from, (to, migrate));
+
+    self
+  }
+
+  /// Writes `value` with the current version.
+  pub fn to_bytes(&self, value: &T, endian: ByteEndian) -> EncoderResult<Vec<u8>> {
+    let mut encoder = ByteEncoder::new(endian);
+
+    encoder.encode_u32(self.version);
+    encoder.encode_value(value);
+
+    encoder.finish()
+  }
+
+  pub fn from_bytes_migrated(&self, bytes: &[u8], endian: ByteEndian) -> DecoderResult<T> {
+    let mut decoder = ByteDecoder::new(bytes, endian);
+    let mut version = decoder.decode_u32()?;
+    let mut payload = bytes[decoder.position()..].to_vec();
+
+    while version != self.version {
+      let (to, migrate) = self.migrations.get(&version).ok_or_else(|| DecoderError::unknown_version(version))?;
+
+      payload = migrate(&payload, endian)?;
+      version = *to;
+    }
+
+    T::from_bytes(&payload, endian)
+  }
+}
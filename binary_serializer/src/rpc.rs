@@ -0,0 +1,109 @@
+use std::any::Any;
+use std::collections::HashMap;
+
+use crate::common::ByteEndian;
+use crate::decoder::{ByteDecoder, Decoder, DecoderError, DecoderResult, Deserializer};
+use crate::encoder::{ByteEncoder, Encoder, Serializer};
+
+pub type RpcEncodeFn = fn(&dyn Any, &mut ByteEncoder) -> bool;
+pub type RpcDecodeFn = fn(&mut ByteDecoder) -> DecoderResult<Box<dyn Any>>;
+
+/// Type erased `(encode, decode)` pair for one side of an rpc method.
+#[derive(Copy, Clone)]
+pub struct RpcCodec {
+  pub encode: RpcEncodeFn,
+  pub decode: RpcDecodeFn,
+}
+
+impl RpcCodec {
+  pub fn of<T: Serializer + Deserializer + 'static>() -> Self {
+    Self {
+      encode: |value, encoder| match value.downcast_ref::<T>() {
+        Some(value) => {
+          encoder.encode_value(value);
+          true
+        }
+        None => false
+      },
+      decode: |decoder| Ok(Box::new(decoder.decode_value::<T>()?)),
+    }
+  }
+}
+
+#[derive(Copy, Clone)]
+pub struct RpcMethod {
+  pub request: RpcCodec,
+  pub response: RpcCodec,
+}
+
+/// Maps `u16` opcodes to request/response codecs.
+///
+/// Messages are written as the opcode followed by the payload,
+/// `dispatch_*` reads the opcode and routes the rest to the registered decoder.
+#[derive(Default, Clone)]
+pub struct RpcTable {
+  methods: HashMap<u16, RpcMethod>,
+}
+
+impl RpcTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Registers `Req`/`Res` for `opcode`, replacing any previous registration.
+  pub fn register<Req, Res>(mut self, opcode: u16) -> Self
+    where Req: Serializer + Deserializer + 'static,
+          Res: Serializer + Deserializer + 'static {
+    self.methods.insert(opcode, RpcMethod {
+      request: RpcCodec::of::<Req>(),
+      response: RpcCodec::of::<Res>(),
+    });
+
+    self
+  }
+
+  pub fn method(&self, opcode: u16) -> Option<&RpcMethod> {
+    self.methods.get(&opcode)
+  }
+
+  /// Returns `None` if `opcode` isn't registered or `request` isn't its request type,
+  /// panics like [`ToBytes::to_bytes`](crate::encoder::ToBytes::to_bytes) if encoding fails.
+  pub fn encode_request(&self, opcode: u16, request: &dyn Any, endian: ByteEndian) -> Option<Vec<u8>> {
+    self.encode_with(opcode, request, endian, |method| method.request)
+  }
+
+  /// Returns `None` if `opcode` isn't registered or `response` isn't its response type,
+  /// panics like [`ToBytes::to_bytes`](crate::encoder::ToBytes::to_bytes) if encoding fails.
+  pub fn encode_response(&self, opcode: u16, response: &dyn Any, endian: ByteEndian) -> Option<Vec<u8>> {
+    self.encode_with(opcode, response, endian, |method| method.response)
+  }
+
+  pub fn dispatch_request(&self, bytes: &[u8], endian: ByteEndian) -> DecoderResult<(u16, Box<dyn Any>)> {
+    self.dispatch_with(bytes, endian, |method| method.request)
+  }
+
+  pub fn dispatch_response(&self, bytes: &[u8], endian: ByteEndian) -> DecoderResult<(u16, Box<dyn Any>)> {
+    self.dispatch_with(bytes, endian, |method| method.response)
+  }
+
+  fn encode_with(&self, opcode: u16, value: &dyn Any, endian: ByteEndian, codec: fn(&RpcMethod) -> RpcCodec) -> Option<Vec<u8>> {
+    let codec = codec(self.method(opcode)?);
+    let mut encoder = ByteEncoder::new(endian);
+
+    encoder.encode_u16(opcode);
+
+    if (codec.encode)(value, &mut encoder) {
+      Some(encoder.finish_or_panic())
+    } else {
+      None
+    }
+  }
+
+  fn dispatch_with(&self, bytes: &[u8], endian: ByteEndian, codec: fn(&RpcMethod) -> RpcCodec) -> DecoderResult<(u16, Box<dyn Any>)> {
+    let mut decoder = ByteDecoder::new(bytes, endian);
+    let opcode = decoder.decode_u16()?;
+    let method = self.method(opcode).ok_or_else(|| DecoderError::unknown_opcode(opcode))?;
+
+    Ok((opcode, (codec(method).decode)(&mut decoder)?))
+  }
+}
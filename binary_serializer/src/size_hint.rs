@@ -0,0 +1,226 @@
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, BinaryHeap, LinkedList, VecDeque};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::marker::{PhantomData, PhantomPinned};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+#[cfg(feature = "binary_serializer_derive")]
+pub use binary_serializer_derive::SizeHint;
+
+use crate::common::FixedSize;
+
+/// Amount of bytes a value encodes to with the default [`FixedCodec`](crate::codec::FixedCodec),
+/// lets [`ToBytes::to_bytes_sized`](crate::encoder::ToBytes::to_bytes_sized) allocate once up front.
+///
+/// Only a hint, encoding never relies on it being right.
+pub trait SizeHint {
+  fn encoded_size(&self) -> usize;
+}
+
+macro_rules! impl_size_hint_fixed {
+  ($($type:ty),+ $(,)?) => {
+    $(impl SizeHint for $type {
+      fn encoded_size(&self) -> usize {
+        <$type as FixedSize>::WIRE_SIZE
+      }
+    })+
+  };
+}
+
+impl_size_hint_fixed!(
+  u8, u16, u32, u64, u128, usize,
+  i8, i16, i32, i64, i128, isize,
+  f32, f64, bool, char, Duration
+);
+
+#[cfg(feature = "half")]
+impl_size_hint_fixed!(half::f16);
+
+#[cfg(feature = "std")]
+impl_size_hint_fixed!(Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6);
+
+#[cfg(feature = "std")]
+impl SizeHint for IpAddr {
+  fn encoded_size(&self) -> usize {
+    1 + match self {
+      IpAddr::V4(addr) => addr.encoded_size(),
+      IpAddr::V6(addr) => addr.encoded_size(),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl SizeHint for SocketAddr {
+  fn encoded_size(&self) -> usize {
+    1 + match self {
+      SocketAddr::V4(addr) => addr.encoded_size(),
+      SocketAddr::V6(addr) => addr.encoded_size(),
+    }
+  }
+}
+
+impl<T: ?Sized> SizeHint for PhantomData<T> {
+  fn encoded_size(&self) -> usize {
+    0
+  }
+}
+
+impl SizeHint for PhantomPinned {
+  fn encoded_size(&self) -> usize {
+    0
+  }
+}
+
+impl<T: SizeHint> SizeHint for Range<T> {
+  fn encoded_size(&self) -> usize {
+    self.start.encoded_size() + self.end.encoded_size()
+  }
+}
+
+impl<T: SizeHint> SizeHint for RangeInclusive<T> {
+  fn encoded_size(&self) -> usize {
+    self.start().encoded_size() + self.end().encoded_size()
+  }
+}
+
+impl<T: SizeHint + ?Sized> SizeHint for &T {
+  fn encoded_size(&self) -> usize {
+    (**self).encoded_size()
+  }
+}
+
+impl SizeHint for str {
+  fn encoded_size(&self) -> usize {
+    #[cfg(not(feature = "utf16_strings"))]
+    return 8 + self.len();
+
+    #[cfg(feature = "utf16_strings")]
+    return 8 + 2 * self.encode_utf16().count();
+  }
+}
+
+impl SizeHint for String {
+  fn encoded_size(&self) -> usize {
+    self.as_str().encoded_size()
+  }
+}
+
+impl<T: SizeHint> SizeHint for [T] {
+  fn encoded_size(&self) -> usize {
+    8 + self.iter().map(T::encoded_size).sum::<usize>()
+  }
+}
+
+impl<T: SizeHint> SizeHint for Vec<T> {
+  fn encoded_size(&self) -> usize {
+    self.as_slice().encoded_size()
+  }
+}
+
+macro_rules! impl_size_hint_sequence {
+  ($($sequence:ident),+) => {
+    $(impl<T: SizeHint> SizeHint for $sequence<T> {
+      fn encoded_size(&self) -> usize {
+        8 + self.iter().map(T::encoded_size).sum::<usize>()
+      }
+    })+
+  };
+}
+
+impl_size_hint_sequence!(VecDeque, LinkedList, BinaryHeap);
+
+impl<T: SizeHint, const N: usize> SizeHint for [T; N] {
+  fn encoded_size(&self) -> usize {
+    self.iter().map(T::encoded_size).sum()
+  }
+}
+
+impl<T: SizeHint> SizeHint for Option<T> {
+  fn encoded_size(&self) -> usize {
+    1 + self.as_ref().map_or(0, T::encoded_size)
+  }
+}
+
+#[cfg(feature = "std")]
+impl<K: SizeHint, V: SizeHint, S> SizeHint for HashMap<K, V, S> {
+  fn encoded_size(&self) -> usize {
+    8 + self.iter().map(|(key, value)| key.encoded_size() + value.encoded_size()).sum::<usize>()
+  }
+}
+
+impl<K: SizeHint, V: SizeHint> SizeHint for BTreeMap<K, V> {
+  fn encoded_size(&self) -> usize {
+    8 + self.iter().map(|(key, value)| key.encoded_size() + value.encoded_size()).sum::<usize>()
+  }
+}
+
+#[cfg(feature = "std")]
+impl<T: SizeHint, S> SizeHint for HashSet<T, S> {
+  fn encoded_size(&self) -> usize {
+    8 + self.iter().map(T::encoded_size).sum::<usize>()
+  }
+}
+
+impl<T: SizeHint> SizeHint for BTreeSet<T> {
+  fn encoded_size(&self) -> usize {
+    8 + self.iter().map(T::encoded_size).sum::<usize>()
+  }
+}
+
+impl<B: SizeHint + ToOwned + ?Sized> SizeHint for Cow<'_, B> {
+  fn encoded_size(&self) -> usize {
+    (**self).encoded_size()
+  }
+}
+
+macro_rules! impl_size_hint_pointer {
+  ($($pointer:ident),+) => {
+    $(impl<T: SizeHint + ?Sized> SizeHint for $pointer<T> {
+      fn encoded_size(&self) -> usize {
+        (**self).encoded_size()
+      }
+    })+
+  };
+}
+
+impl_size_hint_pointer!(Box, Rc, Arc);
+
+macro_rules! impl_size_hint_tuple {
+  ($($name:ident),*) => {
+    impl <$($name: SizeHint),*> SizeHint for ($($name,)*) {
+      #[allow(non_snake_case)]
+      fn encoded_size(&self) -> usize {
+        let ($($name,)*) = self;
+
+        0 $(+ $name.encoded_size())*
+      }
+    }
+  };
+}
+
+impl_size_hint_tuple!();
+impl_size_hint_tuple!(A);
+impl_size_hint_tuple!(A, B);
+impl_size_hint_tuple!(A, B, C);
+impl_size_hint_tuple!(A, B, C, D);
+impl_size_hint_tuple!(A, B, C, D, E);
+impl_size_hint_tuple!(A, B, C, D, E, F);
+impl_size_hint_tuple!(A, B, C, D, E, F, G);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K, L);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K, L, M);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K, L, M, N);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q);
+impl_size_hint_tuple!(A, B, C, D, E, F, G, J, K, L, M, N, O, P, Q, R);
\ No newline at end of file
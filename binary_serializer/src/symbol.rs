@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use crate::decoder::{Decoder, DecoderError, DecoderResult, Deserializer};
+use crate::encoder::{Encoder, Serializer};
+
+/// Id of a string interned in a [`SymbolTable`], written as just the `u32`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Symbol(pub u32);
+
+impl Serializer for Symbol {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u32(self.0);
+  }
+}
+
+impl Deserializer for Symbol {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decoder.decode_u32().map(Symbol)
+  }
+}
+
+/// Interns strings as [`Symbol`]s, written as the list of strings in id order.
+///
+/// Symbols are only ids, decode the table alongside them and resolve after decoding.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SymbolTable {
+  strings: Vec<String>,
+  ids: HashMap<String, Symbol>,
+}
+
+impl SymbolTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the symbol of `value`, adding it if it wasn't interned yet.
+  pub fn intern(&mut self, value: &str) -> Symbol {
+    if let Some(&symbol) = self.ids.get(value) {
+      return symbol;
+    }
+
+    let symbol = Symbol(self.strings.len() as u32);
+
+    self.strings.push(value.to_string());
+    self.ids.insert(value.to_string(), symbol);
+
+    symbol
+  }
+
+  pub fn resolve(&self, symbol: Symbol) -> Option<&str> {
+    self.strings.get(symbol.0 as usize).map(String::as_str)
+  }
+
+  pub fn get(&self, value: &str) -> Option<Symbol> {
+    self.ids.get(value).copied()
+  }
+
+  pub fn len(&self) -> usize {
+    self.strings.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.strings.is_empty()
+  }
+}
+
+impl Serializer for SymbolTable {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_slice(&self.strings);
+  }
+}
+
+/// Rejects a string that appears twice, interning never writes one.
+impl Deserializer for SymbolTable {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    let strings = decoder.decode_slice::<String>()?;
+    let mut ids = HashMap::with_capacity(strings.len());
+
+    for (idx, value) in strings.iter().enumerate() {
+      if ids.insert(value.clone(), Symbol(idx as u32)).is_some() {
+        return Err(DecoderError::custom(format!("symbol `{}` is interned twice", value)));
+      }
+    }
+
+    Ok(Self { strings, ids })
+  }
+}
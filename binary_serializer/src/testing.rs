@@ -0,0 +1,21 @@
+//! Checks for tests of types with hand written or derived impls.
+
+use core::fmt::Debug;
+
+use crate::common::ByteEndian;
+use crate::decoder::{Deserializer, FromBytes};
+use crate::encoder::{Serializer, ToBytes};
+
+/// Panics unless `value` decodes back to itself in both `Little` and `Big` endian, using up all its bytes,
+/// and the decoded value encodes to the same bytes again.
+pub fn assert_roundtrip<T: Serializer + Deserializer + PartialEq + Debug>(value: T) {
+  for endian in [ByteEndian::Little, ByteEndian::Big] {
+    let bytes = value.to_bytes(endian);
+    let decoded = T::from_bytes_exact(&bytes, endian);
+
+    assert_eq!(decoded.as_ref(), Ok(&value), "decoding {:?} endian", endian);
+
+    let decoded = decoded.unwrap();
+    assert_eq!(decoded.to_bytes(endian), bytes, "re-encoding {:?} endian", endian);
+  }
+}
\ No newline at end of file
@@ -0,0 +1,53 @@
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::common::ByteEndian;
+use crate::decoder::{ByteDecoder, Decoder, DecoderError, DecoderResult};
+
+/// Layout of the data being transcoded.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Schema {
+  /// A length prefixed `Vec<T>`/`&[T]` of a primitive that's `width` bytes wide.
+  PrimitiveSlice { width: usize },
+}
+
+/// Converts serialized data from `from` endianness to `to` by byte-swapping its primitives,
+/// without decoding it into typed values.
+pub fn transcode(bytes: &[u8], from: ByteEndian, to: ByteEndian, schema: Schema) -> DecoderResult<Vec<u8>> {
+  let mut bytes = bytes.to_vec();
+  transcode_in_place(&mut bytes, from, to, schema)?;
+
+  Ok(bytes)
+}
+
+/// Same as [`transcode`] but swaps in place, `bytes` is left untouched on error.
+pub fn transcode_in_place(bytes: &mut [u8], from: ByteEndian, to: ByteEndian, schema: Schema) -> DecoderResult<()> {
+  match schema {
+    Schema::PrimitiveSlice { width } => {
+      let mut decoder = ByteDecoder::new(bytes, from);
+      let len = decoder.decode_u64()?;
+      let prefix = decoder.position();
+      let limit = (usize::MAX - prefix) / width.max(1);
+
+      let len = usize::try_from(len).map_err(|_| DecoderError::length_too_large(usize::MAX, limit))?;
+      let end = len
+        .checked_mul(width)
+        .and_then(|size| size.checked_add(prefix))
+        .ok_or_else(|| DecoderError::length_too_large(len, limit))?;
+
+      if end > bytes.len() {
+        return Err(DecoderError::not_enough_bytes(format!("[{} bytes; {}]", width, len), prefix));
+      }
+
+      if from.is_native() != to.is_native() {
+        bytes[..prefix].reverse();
+
+        if width > 1 {
+          bytes[prefix..end].chunks_exact_mut(width).for_each(<[u8]>::reverse);
+        }
+      }
+
+      Ok(())
+    }
+  }
+}
@@ -0,0 +1,99 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub use binary_serializer::prelude::*;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Debug, PartialEq, DeserializerBorrowed)]
+struct Packet<'a> {
+  id: u32,
+  payload: &'a [u8],
+  inner: Inner<'a>,
+}
+
+#[derive(Debug, PartialEq, DeserializerBorrowed)]
+struct Inner<'a>(&'a [u8], bool);
+
+#[test]
+fn no_allocations() {
+  let bytes = (69u32, vec![1u8, 2, 3], vec![4u8, 5], true).to_bytes(ByteEndian::Little);
+
+  let before = ALLOCATIONS.load(Ordering::SeqCst);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+  let packet = Packet::decode_borrowed(&mut decoder);
+  let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+  assert_eq!(before, after);
+  assert_eq!(packet, Ok(Packet { id: 69, payload: &[1, 2, 3], inner: Inner(&[4, 5], true) }));
+}
+
+#[test]
+fn truncated() {
+  let mut bytes = (69u32, vec![1u8, 2, 3]).to_bytes(ByteEndian::Little);
+  bytes.pop();
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+
+  assert!(matches!(
+    Packet::decode_borrowed(&mut decoder),
+    Err(DecoderError::NotEnoughBytes { .. })
+  ));
+}
+
+#[cfg(not(feature = "utf16_strings"))]
+#[test]
+fn str() {
+  let bytes = (String::from("héllo"), vec![b'a', 0xff]).to_bytes(ByteEndian::Little);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+
+  let before = ALLOCATIONS.load(Ordering::SeqCst);
+  let name = <&str>::decode_borrowed(&mut decoder);
+  let after = ALLOCATIONS.load(Ordering::SeqCst);
+
+  assert_eq!(before, after);
+  assert_eq!(name, Ok("héllo"));
+  assert_eq!(<&str>::decode_borrowed(&mut decoder), Err(DecoderError::invalid_utf8(23)));
+}
+
+#[cfg(not(feature = "utf16_strings"))]
+#[test]
+fn decode_str_and_bytes() {
+  let text = "borrowed ".repeat(1000);
+  let bytes = (text.clone(), vec![7u8; 4096], 69u8).to_bytes(ByteEndian::Big);
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Big);
+  let decoded = (decoder.decode_str().unwrap(), decoder.decode_bytes().unwrap(), decoder.decode_u8());
+
+  assert_eq!(decoded, (text.as_str(), &[7u8; 4096][..], Ok(69)));
+
+  // both point straight into the input
+  assert_eq!(decoded.0.as_ptr(), bytes[8..].as_ptr());
+  assert_eq!(decoded.1.as_ptr(), bytes[8 + text.len() + 8..].as_ptr());
+
+  let mut decoder = ByteDecoder::new(&bytes[..100], ByteEndian::Big);
+  assert!(matches!(decoder.decode_str(), Err(DecoderError::NotEnoughBytes { .. })));
+
+  // the length prefix is checked against `max_len` like any other
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Big).with_max_len(4096);
+  assert_eq!(decoder.decode_str(), Err(DecoderError::length_too_large(text.len(), 4096)));
+
+  let mut decoder = ByteDecoder::new(&bytes[8 + text.len()..], ByteEndian::Big).with_max_len(4095);
+  assert_eq!(decoder.decode_bytes(), Err(DecoderError::length_too_large(4096, 4095)));
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+#![cfg(feature = "compress")]
+
+use binary_serializer::compress::{from_bytes_compressed, to_bytes_compressed, CompressedDecoder, CompressedEncoder};
+use binary_serializer::prelude::*;
+
+#[test]
+fn round_trip() {
+  let source = (0..1000u32).map(|idx| (idx % 7, String::from("repeated"))).collect::<Vec<_>>();
+  let bytes = to_bytes_compressed(&source, ByteEndian::Big, 6).unwrap();
+
+  assert!(bytes.len() < source.to_bytes(ByteEndian::Big).len() / 10);
+  assert_eq!(from_bytes_compressed(&bytes, ByteEndian::Big), Ok(source.clone()));
+  assert_eq!(from_bytes_compressed(&to_bytes_compressed(&source, ByteEndian::Big, 0).unwrap(), ByteEndian::Big), Ok(source));
+
+  let mut encoder = CompressedEncoder::new(ByteEndian::Little, 9);
+  encoder.encode_value(&1u8);
+  encoder.encoder().encode_u16(2);
+
+  let bytes = encoder.finish().unwrap();
+  let decompressed = CompressedDecoder::new(&bytes, ByteEndian::Little).unwrap();
+  let mut decoder = decompressed.decoder();
+
+  assert_eq!(decoder.decode_value::<(u8, u16)>(), Ok((1, 2)));
+  assert_eq!(decoder.remaining(), 0);
+}
+
+#[test]
+fn invalid() {
+  assert!(matches!(from_bytes_compressed::<u64>(&[0xff; 16], ByteEndian::Little), Err(DecoderError::Decompression { .. })));
+
+  let bytes = to_bytes_compressed(&(1u64, 2u64), ByteEndian::Little, 6).unwrap();
+  assert_eq!(from_bytes_compressed::<u64>(&bytes, ByteEndian::Little), Err(DecoderError::trailing_bytes(8)));
+
+  // 4096 bytes and their length prefix
+  let bytes = to_bytes_compressed(&vec![0u8; 4096], ByteEndian::Little, 9).unwrap();
+  assert!(CompressedDecoder::with_limit(&bytes, ByteEndian::Little, 4104).is_ok());
+  assert_eq!(
+    CompressedDecoder::with_limit(&bytes, ByteEndian::Little, 4103).err(),
+    Some(DecoderError::decompressed_too_large(4103))
+  );
+}
\ No newline at end of file
@@ -0,0 +1,332 @@
+use std::io::Read;
+
+pub use binary_serializer::prelude::*;
+
+#[test]
+fn length_overflow() {
+  let len = usize::MAX / 8 + 1;
+  let bytes = len.to_bytes(ByteEndian::Little);
+
+  assert_eq!(
+    Vec::<u64>::from_bytes(&bytes, ByteEndian::Little),
+    Err(DecoderError::length_too_large(len, isize::MAX as usize / 8))
+  );
+}
+
+#[test]
+fn slice_view() {
+  let source = (0..1000u32).map(|idx| (idx, idx as f64 / 2.0, idx % 2 == 0)).collect::<Vec<_>>();
+  let bytes = (source.clone(), 69u8).to_bytes(ByteEndian::Big);
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Big);
+  let view = decoder.decode_slice_view::<(u32, f64, bool)>().unwrap();
+
+  assert_eq!(view.len(), source.len());
+  assert_eq!(decoder.decode_u8(), Ok(69));
+
+  let decoded = Vec::<(u32, f64, bool)>::from_bytes(&bytes, ByteEndian::Big).unwrap();
+
+  for idx in [999, 0, 420, 69, 1] {
+    assert_eq!(view.get(idx), Ok(decoded[idx]));
+  }
+
+  assert_eq!(view.get(1000), Err(DecoderError::index_out_of_bounds(1000, 1000)));
+
+  // elements are checked like they would be by `decode_slice`
+  let bytes = vec![2u8, 1].to_bytes(ByteEndian::Little);
+  let view = ByteDecoder::new(&bytes, ByteEndian::Little).with_strict_bools().decode_slice_view::<bool>().unwrap();
+  assert_eq!(view.get(0), Err(DecoderError::invalid_bool(2)));
+  assert_eq!(view.get(1), Ok(true));
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little).with_varint_lengths();
+  assert!(matches!(decoder.decode_slice_view::<usize>(), Err(DecoderError::Unsupported { .. })));
+}
+
+#[test]
+fn canonical() {
+  let source = (69u32, true, String::from("canonical"));
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(<(u32, bool, String)>::from_bytes_canonical(&bytes, ByteEndian::Little), Ok(source));
+
+  let mut bytes = (69u32, 2u8).to_bytes(ByteEndian::Little);
+  assert_eq!(<(u32, bool)>::from_bytes(&bytes, ByteEndian::Little), Ok((69, true)));
+  assert_eq!(<(u32, bool)>::from_bytes_canonical(&bytes, ByteEndian::Little), Err(DecoderError::non_canonical(4)));
+
+  bytes[4] = 1;
+  bytes.push(0);
+  assert_eq!(<(u32, bool)>::from_bytes_canonical(&bytes, ByteEndian::Little), Err(DecoderError::non_canonical(5)));
+}
+
+#[test]
+fn peek_len() {
+  let bytes = (vec![1u16, 2, 3], String::from("hi")).to_bytes(ByteEndian::Big);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Big);
+
+  assert_eq!(decoder.peek_len(), Ok(3));
+  assert_eq!(decoder.peek_len(), Ok(3));
+  assert_eq!(decoder.position(), 0);
+  assert_eq!(decoder.decode_value::<Vec<u16>>(), Ok(vec![1, 2, 3]));
+
+  assert_eq!(decoder.peek_len(), Ok(2));
+  assert_eq!(decoder.decode_value::<String>(), Ok(String::from("hi")));
+
+  assert!(matches!(decoder.peek_len(), Err(DecoderError::NotEnoughBytes { .. })));
+}
+
+#[test]
+fn max_len() {
+  let bytes = vec![0u8; 100].to_bytes(ByteEndian::Little);
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little).with_max_len(99);
+  assert_eq!(decoder.decode_value::<Vec<u8>>(), Err(DecoderError::length_too_large(100, 99)));
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little).with_max_len(100);
+  assert_eq!(decoder.decode_value::<Vec<u8>>(), Ok(vec![0; 100]));
+
+  let bytes = (u32::MAX as usize + 1).to_bytes(ByteEndian::Little);
+  assert_eq!(
+    Vec::<u8>::from_bytes(&bytes, ByteEndian::Little),
+    Err(DecoderError::length_too_large(u32::MAX as usize + 1, DEFAULT_MAX_LEN))
+  );
+
+  // claims far more elements than there are bytes, fails on the missing input rather than the allocation
+  let bytes = (u32::MAX as usize).to_bytes(ByteEndian::Little);
+  assert!(matches!(Vec::<u64>::from_bytes(&bytes, ByteEndian::Little), Err(DecoderError::NotEnoughBytes { .. })));
+
+  // checked up front for primitives in either endianness
+  let bytes = (u32::MAX as usize, 1u64, 2u64).to_bytes(ByteEndian::Big);
+  assert_eq!(Vec::<u64>::from_bytes(&bytes, ByteEndian::Big), Err(DecoderError::not_enough_bytes("u64", 24)));
+}
+
+/// Hands out a single byte per `read`, then fails once `fail_at` is reached.
+struct Trickle<'a> {
+  bytes: &'a [u8],
+  fail_at: usize,
+}
+
+impl Read for Trickle<'_> {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    if self.fail_at == 0 {
+      return Err(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset"));
+    }
+
+    let Some((&first, rest)) = self.bytes.split_first() else { return Ok(0) };
+
+    buf[0] = first;
+    self.bytes = rest;
+    self.fail_at -= 1;
+
+    Ok(1)
+  }
+}
+
+#[test]
+fn read_decoder() {
+  let records = (0..100u32).map(|idx| (idx, idx.to_string(), vec![idx as u8; idx as usize])).collect::<Vec<_>>();
+  let bytes = records.iter().flat_map(|it| it.to_bytes(ByteEndian::Big)).collect::<Vec<_>>();
+
+  let mut decoder = ReadDecoder::new(Trickle { bytes: &bytes, fail_at: usize::MAX }, ByteEndian::Big);
+
+  for record in &records {
+    assert_eq!(decoder.decode_value::<(u32, String, Vec<u8>)>().as_ref(), Ok(record));
+  }
+
+  assert_eq!(decoder.position(), bytes.len());
+  assert_eq!(decoder.decode_u8(), Err(DecoderError::not_enough_bytes("u8", bytes.len())));
+
+  let bytes = (69u64, String::from("hello")).to_bytes(ByteEndian::Little);
+  assert_eq!(<(u64, String)>::from_reader(bytes.as_slice(), ByteEndian::Little), Ok((69, String::from("hello"))));
+
+  assert!(matches!(
+    <(u64, String)>::from_reader(Trickle { bytes: &bytes, fail_at: 10 }, ByteEndian::Little),
+    Err(DecoderError::Io { kind: std::io::ErrorKind::ConnectionReset, .. })
+  ));
+}
+
+#[cfg(not(feature = "utf16_strings"))]
+#[test]
+fn str_validate() {
+  let mut bytes = (69u8, String::from("añb")).to_bytes(ByteEndian::Little);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+
+  assert_eq!(decoder.decode_u8(), Ok(69));
+  assert_eq!(decoder.decode_str_validate(), Ok("añb"));
+
+  // second byte of `ñ` is no longer a continuation byte
+  bytes[1 + 8 + 2] = b'x';
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+  assert_eq!(decoder.decode_u8(), Ok(69));
+  assert_eq!(decoder.decode_str_validate(), Err(DecoderError::invalid_utf8(1 + 8 + 1)));
+}
+
+#[test]
+fn primitive_fast_path() {
+  let source = (0..16384u32).map(|idx| idx.wrapping_mul(2654435761)).collect::<Vec<_>>();
+  let bytes = source.to_bytes(ByteEndian::Native);
+
+  // `ReadDecoder` always decodes element by element
+  let slow = Vec::<u32>::from_reader(bytes.as_slice(), ByteEndian::Native).unwrap();
+  assert_eq!(Vec::<u32>::from_bytes(&bytes, ByteEndian::Native), Ok(slow));
+
+  let floats = vec![1.5f64, -0.0, f64::MAX, f64::MIN_POSITIVE];
+  assert_eq!(Vec::<f64>::from_bytes(&floats.to_bytes(ByteEndian::Native), ByteEndian::Native), Ok(floats));
+
+  let bytes = &bytes[..bytes.len() - 1];
+  assert_eq!(
+    Vec::<u32>::from_bytes(bytes, ByteEndian::Native),
+    Err(DecoderError::not_enough_bytes("u32", 8 + 16383 * 4))
+  );
+}
+
+#[test]
+fn exact() {
+  let mut bytes = (69u32, String::from("exact")).to_bytes(ByteEndian::Big);
+  assert_eq!(<(u32, String)>::from_bytes_exact(&bytes, ByteEndian::Big), Ok((69, String::from("exact"))));
+
+  bytes.extend([0; 3]);
+  assert_eq!(<(u32, String)>::from_bytes(&bytes, ByteEndian::Big), Ok((69, String::from("exact"))));
+  assert_eq!(<(u32, String)>::from_bytes_exact(&bytes, ByteEndian::Big), Err(DecoderError::trailing_bytes(3)));
+}
+
+#[test]
+fn seek() {
+  let bytes = (69u32, vec![1u8, 2, 3], 420u16).to_bytes(ByteEndian::Little);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+
+  assert_eq!(decoder.decode_u32(), Ok(69));
+  assert_eq!(decoder.remaining(), bytes.len() - 4);
+
+  let skip = decoder.peek_len().unwrap();
+  assert_eq!(decoder.seek(decoder.position() + 8 + skip), Ok(()));
+  assert_eq!(decoder.remaining_bytes(), &420u16.to_le_bytes());
+  assert_eq!(decoder.decode_u16(), Ok(420));
+  assert_eq!(decoder.remaining(), 0);
+
+  assert_eq!(decoder.seek(bytes.len() + 1), Err(DecoderError::index_out_of_bounds(bytes.len() + 1, bytes.len())));
+  assert_eq!(decoder.seek(4), Ok(()));
+  assert_eq!(decoder.decode_slice::<u8>(), Ok(vec![1, 2, 3]));
+}
+
+#[test]
+fn with_len() {
+  let records = (0..10u8).map(|idx| (idx, vec![idx; idx as usize])).collect::<Vec<_>>();
+  let bytes = records.iter().flat_map(|it| it.to_bytes(ByteEndian::Big)).collect::<Vec<_>>();
+
+  let mut rest = bytes.as_slice();
+
+  for record in &records {
+    let (value, len) = <(u8, Vec<u8>)>::from_bytes_with_len(rest, ByteEndian::Big).unwrap();
+
+    assert_eq!(&value, record);
+    assert_eq!(len, 1 + 8 + record.0 as usize);
+
+    rest = &rest[len..];
+  }
+
+  assert!(rest.is_empty());
+}
+
+#[derive(Debug, PartialEq)]
+struct Port(u16);
+
+impl Deserializer for Port {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    match decoder.decode_u16()? {
+      0 => Err(DecoderError::custom("port number out of range")),
+      port => Ok(Port(port)),
+    }
+  }
+}
+
+#[test]
+fn custom_error() {
+  assert_eq!(Port::from_bytes(&8080u16.to_bytes(ByteEndian::Big), ByteEndian::Big), Ok(Port(8080)));
+
+  let err = Port::from_bytes(&[0, 0], ByteEndian::Big).unwrap_err();
+  assert_eq!(err, DecoderError::Custom(String::from("port number out of range")));
+  assert_eq!(err.to_string(), "port number out of range");
+}
+
+#[test]
+fn peek() {
+  let bytes = [2u8, 0xaa, 0xbb];
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+
+  assert_eq!(decoder.peek_u8(), Ok(2));
+  assert_eq!(decoder.peek_bytes::<3>(), Ok(bytes));
+  assert_eq!(decoder.peek_bytes::<0>(), Ok([]));
+  assert_eq!(decoder.position(), 0);
+
+  let value = match decoder.peek_u8().unwrap() {
+    2 => decoder.decode_value::<(u8, u16)>().map(|(_, value)| value),
+    _ => unreachable!(),
+  };
+
+  assert_eq!(value, Ok(0xbbaa));
+  assert_eq!(decoder.peek_u8(), Err(DecoderError::not_enough_bytes("[u8; 1]", 3)));
+  assert!(ByteDecoder::new(&bytes, ByteEndian::Little).peek_bytes::<4>().is_err());
+}
+
+#[test]
+fn strict_bools() {
+  let bytes = [0, 1, 0x42];
+
+  assert_eq!(<(bool, bool, bool)>::from_bytes(&bytes, ByteEndian::Little), Ok((false, true, true)));
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little).with_strict_bools();
+
+  assert_eq!(decoder.decode_value::<[bool; 2]>(), Ok([false, true]));
+  assert_eq!(decoder.decode_bool(), Err(DecoderError::invalid_bool(0x42)));
+}
+
+#[test]
+fn strict_floats() {
+  let bytes = (1.5f32, f32::NAN, vec![0.5f64, f64::INFINITY]).to_bytes(ByteEndian::Native);
+
+  assert!(<(f32, f32, Vec<f64>)>::from_bytes(&bytes, ByteEndian::Native).is_ok());
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Native).with_strict_floats();
+
+  assert_eq!(decoder.decode_f32(), Ok(1.5));
+  assert_eq!(decoder.decode_f32(), Err(DecoderError::non_finite_float("f32")));
+  // caught inside slices as well, which otherwise skip decoding elements one by one
+  assert_eq!(decoder.decode_value::<Vec<f64>>(), Err(DecoderError::non_finite_float("f64")));
+}
+
+#[test]
+fn error_traits() {
+  fn assert_traits<T: Clone + Eq + std::error::Error + Send + Sync + 'static>() {}
+
+  assert_traits::<DecoderError>();
+  assert_traits::<EncoderError>();
+
+  let err = u32::from_bytes(&[1], ByteEndian::Little).unwrap_err();
+  let stored = Some(err.clone());
+
+  assert_eq!(stored, Some(DecoderError::not_enough_bytes("u32", 0)));
+  assert_eq!(err.to_string(), "not enough bytes left to decode `u32` starting at index `0`");
+}
+
+#[test]
+fn slice_iter() {
+  let records = (0..100u32).map(|idx| (idx, idx.to_string())).collect::<Vec<_>>();
+  let bytes = (records.clone(), 69u8).to_bytes(ByteEndian::Big);
+
+  let mut decoder = ReadDecoder::new(bytes.as_slice(), ByteEndian::Big);
+  let mut iter = decoder.decode_slice_iter::<(u32, String)>().unwrap();
+
+  assert_eq!(iter.remaining(), 100);
+  assert_eq!(iter.next(), Some(Ok((0, String::from("0")))));
+  assert_eq!(iter.remaining(), 99);
+  assert_eq!(iter.collect::<DecoderResult<Vec<_>>>().as_deref(), Ok(&records[1..]));
+  assert_eq!(decoder.decode_u8(), Ok(69));
+
+  // stops at the first error instead of decoding garbage after it
+  let mut decoder = ByteDecoder::new(&bytes[..20], ByteEndian::Big);
+  let results = decoder.decode_slice_iter::<(u32, String)>().unwrap().collect::<Vec<_>>();
+
+  assert_eq!(results.len(), 1);
+  assert!(results[0].is_err());
+}
\ No newline at end of file
@@ -1,6 +1,7 @@
 pub use binary_serializer::prelude::*;
 
 use std::fmt::Debug;
+use std::marker::PhantomData;
 
 #[derive(Debug, PartialEq, Serializer, Deserializer)]
 struct Unit;
@@ -74,3 +75,748 @@ fn invalid() {
     enums: vec![Enum::Tuple(69, 420), Enum::Struct { x: 69, y: 420 }],
   });
 }
+
+#[test]
+fn enum_split() {
+  let source = vec![Enum::Unit, Enum::Tuple(69, 420), Enum::Struct { x: 69, y: 420 }];
+  let mut tags = ByteEncoder::new(ByteEndian::Little);
+  let mut payloads = ByteEncoder::new(ByteEndian::Little);
+
+  for value in &source {
+    tags.encode_enum_tag(value);
+    payloads.encode_enum_payload(value);
+  }
+
+  assert_eq!(tags.bytes().len(), source.len() * 8);
+
+  let mut tags = ByteDecoder::new(tags.bytes(), ByteEndian::Little);
+  let mut payloads = ByteDecoder::new(payloads.bytes(), ByteEndian::Little);
+  let parsed = (0..source.len())
+    .map(|_| {
+      let tag = tags.decode_enum_tag::<Enum>()?;
+      payloads.decode_enum_payload(tag)
+    })
+    .collect::<DecoderResult<Vec<Enum>>>();
+
+  assert_eq!(Ok(source), parsed);
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(tag = hash)]
+enum Hashed {
+  Unit,
+  Tuple(u32, u32),
+  Struct { x: u32, y: u32 },
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(tag = hash)]
+enum HashedReordered {
+  Struct { x: u32, y: u32 },
+  Unit,
+  Tuple(u32, u32),
+}
+
+#[test]
+fn hash_tags() {
+  test_valid(Hashed::Unit);
+  test_valid(Hashed::Tuple(69, 420));
+  test_valid(Hashed::Struct { x: 69, y: 420 });
+
+  assert_eq!(Hashed::Unit.to_bytes(ByteEndian::Little), HashedReordered::Unit.to_bytes(ByteEndian::Little));
+  assert_eq!(
+    Hashed::Tuple(69, 420).to_bytes(ByteEndian::Little),
+    HashedReordered::Tuple(69, 420).to_bytes(ByteEndian::Little)
+  );
+  assert_eq!(
+    HashedReordered::from_bytes(&Hashed::Struct { x: 69, y: 420 }.to_bytes(ByteEndian::Big), ByteEndian::Big),
+    Ok(HashedReordered::Struct { x: 69, y: 420 })
+  );
+}
+
+#[derive(Debug, PartialEq, Default, Serializer, Deserializer)]
+#[binary(decode_prefix)]
+struct Header {
+  id: u32,
+  kind: u16,
+  name: String,
+  blob: Vec<u8>,
+}
+
+#[test]
+fn decode_prefix() {
+  let source = Header {
+    id: 69,
+    kind: 420,
+    name: String::from("header"),
+    blob: vec![0; 1024],
+  };
+
+  let bytes = source.to_bytes(ByteEndian::Little);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+  let parsed = Header::decode_prefix(&mut decoder, 5).unwrap();
+
+  assert_eq!(parsed, Header { id: 69, kind: 420, ..Header::default() });
+  assert_eq!(decoder.position(), 6);
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+  assert_eq!(Header::decode_prefix(&mut decoder, bytes.len()), Ok(source));
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+enum Defaulted {
+  A(u32),
+  #[binary(default_variant)]
+  Unknown,
+  B,
+}
+
+#[test]
+fn default_variant() {
+  test_valid(Defaulted::A(69));
+  test_valid(Defaulted::Unknown);
+  test_valid(Defaulted::B);
+
+  assert_eq!(Defaulted::from_bytes(&99usize.to_bytes(ByteEndian::Little), ByteEndian::Little), Ok(Defaulted::Unknown));
+  assert!(Enum::from_bytes(&99usize.to_bytes(ByteEndian::Little), ByteEndian::Little).is_err());
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(field_offsets)]
+struct Counter {
+  name: String,
+  count: u32,
+  tuple: Tuple,
+}
+
+#[test]
+fn field_offsets() {
+  let source = Counter { name: String::from("hits"), count: 69, tuple: Tuple(1, 2) };
+  let mut bytes = source.to_bytes(ByteEndian::Big);
+  let offsets = source.field_offsets(ByteEndian::Big);
+  let name_len = String::from("hits").to_bytes(ByteEndian::Big).len();
+
+  assert_eq!(offsets, vec![("name", 0, name_len), ("count", name_len, 4), ("tuple", name_len + 4, 8)]);
+
+  let (_, offset, len) = offsets[1];
+  bytes[offset..offset + len].copy_from_slice(&420u32.to_be_bytes());
+
+  assert_eq!(
+    Counter::from_bytes(&bytes, ByteEndian::Big),
+    Ok(Counter { name: String::from("hits"), count: 420, tuple: Tuple(1, 2) })
+  );
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Outcome {
+  status: Result<u32, String>,
+  value: Result<Tuple, Enum>,
+}
+
+#[test]
+fn result_fields() {
+  test_valid(Outcome { status: Ok(69), value: Ok(Tuple(69, 420)) });
+  test_valid(Outcome {
+    status: Err(String::from("failed")),
+    value: Err(Enum::Struct { x: 69, y: 420 }),
+  });
+  test_valid(Outcome { status: Ok(0), value: Err(Enum::Unit) });
+  test_valid(vec![Ok(Enum::Tuple(1, 2)), Err(Enum::Unit), Ok(Enum::Unit)] as Vec<Result<Enum, Enum>>);
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Column {
+  #[binary(none = 0xFFFFFFFF)]
+  value: Option<u32>,
+  #[binary(none = -1)]
+  delta: Option<i16>,
+}
+
+#[test]
+fn sentinel_option() {
+  test_valid(Column { value: Some(69), delta: Some(0) });
+  test_valid(Column { value: None, delta: None });
+
+  let bytes = Column { value: None, delta: Some(420) }.to_bytes(ByteEndian::Little);
+  assert_eq!(bytes, [0xff, 0xff, 0xff, 0xff, 0xa4, 0x01]);
+
+  let reserved = Column { value: Some(u32::MAX), delta: None };
+  assert_eq!(reserved.try_to_bytes(ByteEndian::Little), Err(EncoderError::ReservedSentinel));
+  assert_eq!(reserved.to_slice(&mut [0; 16], ByteEndian::Little), Err(EncoderError::ReservedSentinel));
+
+  let bytes = (0u32, u32::MAX).to_bytes(ByteEndian::Little);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+  assert_eq!(decoder.decode_sentinel_option(u32::MAX), Ok(Some(0)));
+  assert_eq!(decoder.decode_sentinel_option(u32::MAX), Ok(None));
+}
+
+#[test]
+#[should_panic(expected = "failed to encode")]
+fn sentinel_option_to_bytes() {
+  // without the field the bytes would decode as something else
+  Column { value: Some(u32::MAX), delta: Some(0) }.to_bytes(ByteEndian::Little);
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(layout_hash = "86f4e226")]
+struct Pinned {
+  id: u64,
+  #[binary(none = 0)]
+  parent: Option<u32>,
+  tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(tag = hash, layout_hash = "411b953c")]
+enum PinnedEnum {
+  A,
+  B(Pinned),
+}
+
+#[test]
+fn layout_hash() {
+  test_valid(PinnedEnum::B(Pinned { id: 69, parent: None, tags: vec![String::from("x")] }));
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(iterative)]
+enum Expr {
+  Leaf(u32),
+  Neg(Box<Expr>),
+  Add { lhs: Box<Self>, tag: String, rhs: Box<Self> },
+}
+
+#[test]
+fn iterative() {
+  test_valid(Expr::Leaf(69));
+  test_valid(Expr::Add {
+    lhs: Box::new(Expr::Neg(Box::new(Expr::Leaf(1)))),
+    tag: String::from("+"),
+    rhs: Box::new(Expr::Add { lhs: Box::new(Expr::Leaf(2)), tag: String::new(), rhs: Box::new(Expr::Leaf(3)) }),
+  });
+
+  let bytes = (1usize, 7usize).to_bytes(ByteEndian::Little);
+  assert_eq!(Expr::from_bytes(&bytes, ByteEndian::Little), Err(DecoderError::invalid_variant("Expr", 7, 3)));
+
+  // deep enough to overflow the stack if decoding recursed
+  let depth = 1_000_000;
+  let mut bytes = Vec::new();
+
+  for _ in 0..depth {
+    bytes.extend(1usize.to_bytes(ByteEndian::Little));
+  }
+
+  bytes.extend((0usize, 420u32).to_bytes(ByteEndian::Little));
+
+  let mut expr = Expr::from_bytes(&bytes, ByteEndian::Little).unwrap();
+  let mut count = 0;
+
+  // dropping recurses too, so take it apart by hand
+  while let Expr::Neg(inner) = expr {
+    expr = *inner;
+    count += 1;
+  }
+
+  assert_eq!(count, depth);
+  assert_eq!(expr, Expr::Leaf(420));
+}
+
+#[test]
+fn unit_variant_bytes() {
+  assert_eq!(Enum::Unit.to_bytes(ByteEndian::Little), 0usize.to_bytes(ByteEndian::Little));
+  assert_eq!(Enum::Tuple(1, 2).to_bytes(ByteEndian::Little), (1usize, 1u32, 2u32).to_bytes(ByteEndian::Little));
+  assert_eq!(Enum::Struct { x: 1, y: 2 }.to_bytes(ByteEndian::Little), (2usize, 1u32, 2u32).to_bytes(ByteEndian::Little));
+
+  let bytes = vec![Enum::Unit, Enum::Tuple(1, 2), Enum::Unit].to_bytes(ByteEndian::Little);
+  assert_eq!(bytes.len(), 8 + 8 + (8 + 4 + 4) + 8);
+
+  assert_eq!(Hashed::Unit.to_bytes(ByteEndian::Little), fnv1a("Unit").to_bytes(ByteEndian::Little));
+  assert_eq!(Small::A.to_bytes(ByteEndian::Little), vec![0]);
+  assert_eq!(Small::C { x: 69 }.to_bytes(ByteEndian::Little), vec![2, 69, 0]);
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(tag = u8)]
+enum Small {
+  A,
+  B(u32),
+  C { x: u16 },
+}
+
+#[test]
+fn tag_width() {
+  test_valid(vec![Small::A, Small::B(69), Small::C { x: 420 }]);
+
+  assert_eq!(Small::from_bytes(&[3], ByteEndian::Little), Err(DecoderError::invalid_variant("Small", 3, 3)));
+  assert_eq!(Small::B(1).to_bytes(ByteEndian::Big), vec![1, 0, 0, 0, 1]);
+}
+
+fn fnv1a(value: &str) -> u32 {
+  value.bytes().fold(0x811c9dc5, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193))
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Cache(Vec<u64>);
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(field_offsets)]
+struct Cached {
+  id: u32,
+  #[binary(skip)]
+  cache: Cache,
+  name: String,
+}
+
+#[test]
+fn skip() {
+  let source = Cached { id: 69, cache: Cache(vec![1, 2, 3]), name: String::from("x") };
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, (69u32, String::from("x")).to_bytes(ByteEndian::Little));
+  assert_eq!(Cached::from_bytes(&bytes, ByteEndian::Little), Ok(Cached { cache: Cache::default(), ..source }));
+  assert_eq!(
+    Cached { id: 1, cache: Cache::default(), name: String::new() }.field_offsets(ByteEndian::Little),
+    vec![("id", 0, 4), ("name", 4, 8)]
+  );
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Wrapper<T>(T);
+
+#[derive(Debug, PartialEq, Default, Serializer, Deserializer)]
+#[binary(decode_prefix, field_offsets)]
+struct Pair<A, B> where A: Clone {
+  a: A,
+  b: Vec<B>,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(tag = u8)]
+enum Either<L, R> {
+  Left(L),
+  Right { value: R },
+  Neither,
+}
+
+#[test]
+fn generics() {
+  test_valid(Wrapper(69u32));
+  test_valid(Wrapper(Wrapper(String::from("nested"))));
+  test_valid(Pair { a: 1u8, b: vec![String::from("x")] });
+  test_valid(vec![Either::Left(69u64), Either::Right { value: Wrapper(1i8) }, Either::Neither]);
+
+  let pair = Pair { a: 1u16, b: vec![2u32] };
+  assert_eq!(pair.field_offsets(ByteEndian::Little), vec![("a", 0, 2), ("b", 2, 12)]);
+
+  let bytes = pair.to_bytes(ByteEndian::Little);
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+  assert_eq!(Pair::<u16, u32>::decode_prefix(&mut decoder, 1), Ok(Pair { a: 1, b: vec![] }));
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Frame {
+  kind: u16,
+  #[binary(endian = "big")]
+  port: u16,
+  #[binary(endian = "little", none = 0)]
+  id: Option<u32>,
+  len: u32,
+}
+
+#[test]
+fn field_endian() {
+  let header = Frame { kind: 1, port: 8080, id: Some(2), len: 3 };
+  test_valid(Frame { id: None, ..header });
+
+  assert_eq!(header.to_bytes(ByteEndian::Little), vec![1, 0, 0x1f, 0x90, 2, 0, 0, 0, 3, 0, 0, 0]);
+  assert_eq!(header.to_bytes(ByteEndian::Big), vec![0, 1, 0x1f, 0x90, 2, 0, 0, 0, 0, 0, 0, 3]);
+  assert_eq!(Frame::from_bytes(&header.to_bytes(ByteEndian::Big), ByteEndian::Big), Ok(header));
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+struct Record {
+  id: u64,
+  name: String,
+  tags: Vec<(char, Option<u16>)>,
+  #[binary(none = 0)]
+  parent: Option<u32>,
+  #[binary(skip)]
+  cached: u32,
+  shape: Shape<f32>,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+#[binary(tag = u8)]
+enum Shape<T> {
+  Empty,
+  Circle(T),
+  Rect { size: [T; 2], label: Box<str> },
+}
+
+#[test]
+fn size_hint() {
+  let records = [
+    Record { id: 1, name: String::from("a"), tags: vec![], parent: None, cached: 0, shape: Shape::Empty },
+    Record {
+      id: 2,
+      name: String::from("ñame"),
+      tags: vec![('x', Some(1)), ('🦀', None)],
+      parent: Some(1),
+      cached: 0,
+      shape: Shape::Rect { size: [1.0, 2.0], label: Box::from("rect") },
+    },
+    Record { id: 3, name: String::new(), tags: vec![], parent: Some(2), cached: 0, shape: Shape::Circle(0.5) },
+  ];
+
+  for record in &records {
+    let bytes = record.to_bytes_sized(ByteEndian::Little);
+
+    assert_eq!(bytes, record.to_bytes(ByteEndian::Little));
+    assert!(record.encoded_size() <= bytes.len());
+  }
+
+  // only a `None` written as its sentinel isn't counted
+  assert_eq!(records[1].encoded_size(), records[1].to_bytes(ByteEndian::Little).len());
+  assert_eq!(records[0].encoded_size() + 4, records[0].to_bytes(ByteEndian::Little).len());
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+enum Chain {
+  End,
+  Link(Box<Chain>),
+  Fork(Box<Chain>, Box<Chain>),
+}
+
+#[test]
+fn recursion_limit() {
+  let chain = |depth: usize| (0..depth).fold(Chain::End, |chain, _| Chain::Link(Box::new(chain)));
+
+  let deep = chain(1000).to_bytes(ByteEndian::Little);
+  let err = ByteDecoder::new(&deep, ByteEndian::Little).decode_value::<Chain>();
+  assert_eq!(err, Err(DecoderError::recursion_limit_exceeded(DEFAULT_MAX_DEPTH)));
+
+  let shallow = chain(20).to_bytes(ByteEndian::Little);
+  assert_eq!(Chain::from_bytes(&shallow, ByteEndian::Little), Ok(chain(20)));
+  assert!(ByteDecoder::new(&shallow, ByteEndian::Little).with_max_depth(20).decode_value::<Chain>().is_err());
+
+  // siblings each get the full depth, only nesting counts
+  let fork = Chain::Fork(Box::new(chain(20)), Box::new(chain(20)));
+  let bytes = fork.to_bytes(ByteEndian::Little);
+  assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).with_max_depth(50).decode_value(), Ok(fork));
+
+  // the outer `decode_value` plus one level per slice
+  let nested = vec![vec![vec![true]]].to_bytes(ByteEndian::Little);
+  assert!(ByteDecoder::new(&nested, ByteEndian::Little).with_max_depth(4).decode_value::<Vec<Vec<Vec<bool>>>>().is_ok());
+  assert!(ByteDecoder::new(&nested, ByteEndian::Little).with_max_depth(3).decode_value::<Vec<Vec<Vec<bool>>>>().is_err());
+}
+
+fn default_retries() -> u8 {
+  3
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct ConfigV1 {
+  name: String,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct ConfigV2 {
+  name: String,
+  #[binary(skip, default = "default_retries")]
+  attempts: u8,
+  #[binary(default)]
+  tags: Vec<String>,
+  #[binary(default = "default_retries")]
+  retries: u8,
+}
+
+#[test]
+fn default() {
+  let old = ConfigV1 { name: String::from("x") }.to_bytes(ByteEndian::Little);
+  let defaulted = ConfigV2 { name: String::from("x"), attempts: 3, tags: vec![], retries: 3 };
+
+  assert_eq!(ConfigV2::from_bytes(&old, ByteEndian::Little), Ok(defaulted));
+  assert_eq!(ConfigV2::from_reader(old.as_slice(), ByteEndian::Little).map(|it| it.retries), Ok(3));
+
+  // fields that are there are still decoded, the skipped one never is
+  let source = ConfigV2 { name: String::from("x"), attempts: 1, tags: vec![String::from("a")], retries: 7 };
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(ConfigV2::from_bytes(&bytes, ByteEndian::Little), Ok(ConfigV2 { attempts: 3, ..source }));
+}
+
+/// Stands in for a type from another crate.
+#[derive(Debug, PartialEq)]
+struct Vec3 {
+  x: f32,
+  y: f32,
+  z: f32,
+}
+
+mod vec3 {
+  use super::*;
+
+  pub fn encode(value: &Vec3, encoder: &mut impl Encoder) {
+    encoder.encode_value(&[value.x, value.y, value.z]);
+  }
+
+  pub fn decode(decoder: &mut impl Decoder) -> DecoderResult<Vec3> {
+    let [x, y, z] = decoder.decode_value::<[f32; 3]>()?;
+
+    Ok(Vec3 { x, y, z })
+  }
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+struct Body {
+  id: u16,
+  #[binary(with = "vec3")]
+  position: Vec3,
+  #[binary(with = "vec3", endian = "big")]
+  velocity: Vec3,
+}
+
+#[test]
+fn with() {
+  let source = Body { id: 1, position: Vec3 { x: 1.0, y: 2.0, z: 3.0 }, velocity: Vec3 { x: -1.0, y: 0.0, z: 0.5 } };
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, (1u16, [1.0f32, 2.0, 3.0]).to_bytes(ByteEndian::Little).into_iter().chain([-1.0f32, 0.0, 0.5].to_bytes(ByteEndian::Big)).collect::<Vec<_>>());
+  assert_eq!(Body::from_bytes(&bytes, ByteEndian::Little), Ok(source));
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(tag = u8)]
+enum Op {
+  Ping = 1,
+  Pong,
+  Close = 8,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[repr(u16)]
+enum Packet {
+  Hello { version: u16 } = 0x10,
+  Data(Vec<u8>) = 0x20,
+  Bye,
+}
+
+#[test]
+fn discriminants() {
+  assert_eq!([Op::Ping, Op::Pong, Op::Close].to_bytes(ByteEndian::Little), vec![1, 2, 8]);
+  assert_eq!(Op::from_bytes(&[8], ByteEndian::Little), Ok(Op::Close));
+  assert_eq!(Op::from_bytes(&[0], ByteEndian::Little), Err(DecoderError::invalid_variant("Op", 0, 3)));
+
+  assert_eq!(Packet::Bye.to_bytes(ByteEndian::Big), 0x21usize.to_bytes(ByteEndian::Big));
+  test_valid(vec![Packet::Hello { version: 2 }, Packet::Data(vec![1, 2]), Packet::Bye]);
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+struct Id<T> {
+  value: u32,
+  _marker: PhantomData<T>,
+}
+
+#[test]
+fn phantom() {
+  let id = Id::<String> { value: 69, _marker: PhantomData };
+  let bytes = id.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, 69u32.to_bytes(ByteEndian::Little));
+  assert_eq!(id.encoded_size(), 4);
+  assert_eq!(Id::from_bytes_exact(&bytes, ByteEndian::Little), Ok(id));
+  assert_eq!(<PhantomData<str>>::WIRE_SIZE, 0);
+
+  // a lifetime that nothing borrows through doesn't stop `Deserializer`
+  test_valid(Scoped { value: 420, _marker: PhantomData });
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Scoped<'a> {
+  value: u32,
+  _marker: PhantomData<&'a str>,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+struct FileHeader {
+  #[binary(fixed = 8)]
+  magic: String,
+  #[binary(fixed = 4)]
+  key: Vec<u8>,
+  version: u8,
+}
+
+#[test]
+fn fixed() {
+  let header = FileHeader { magic: "BINSER".into(), key: vec![1, 2], version: 3 };
+  let bytes = header.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, b"BINSER\0\0\x01\x02\0\0\x03");
+  assert_eq!(header.encoded_size(), bytes.len());
+  assert_eq!(FileHeader::from_bytes_exact(&bytes, ByteEndian::Little), Ok(FileHeader { key: vec![1, 2, 0, 0], ..header }));
+
+  // cut off at a char boundary rather than in the middle of `é`
+  let header = FileHeader { magic: "1234567é".into(), key: vec![1, 2, 3, 4, 5], version: 3 };
+  let decoded = FileHeader::from_bytes_exact(&header.to_bytes(ByteEndian::Little), ByteEndian::Little);
+
+  assert_eq!(decoded, Ok(FileHeader { magic: "1234567".into(), key: vec![1, 2, 3, 4], version: 3 }));
+
+  // errors point into the whole input, not just the field
+  let bytes = (1u32, *b"ab\xffcd\0\0\0", [0u8; 5]).to_bytes(ByteEndian::Little);
+  assert_eq!(<(u32, FileHeader)>::from_bytes(&bytes, ByteEndian::Little), Err(DecoderError::invalid_utf8(6)));
+}
+
+#[derive(Serializer, SizeHint)]
+struct HeaderView<'a, T> {
+  name: &'a str,
+  tags: &'a [u32],
+  extra: Option<&'a T>,
+}
+
+#[test]
+fn borrowed_view() {
+  let tags = vec![1, 2, 3];
+  let view = HeaderView { name: "view", tags: &tags, extra: Some(&69u8) };
+  let bytes = view.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, ("view".to_string(), tags.clone(), Some(69u8)).to_bytes(ByteEndian::Little));
+  assert_eq!(view.encoded_size(), bytes.len());
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+#[binary(versioned)]
+struct PeerV1 {
+  id: u32,
+  name: String,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+#[binary(versioned)]
+struct PeerV2 {
+  id: u32,
+  name: String,
+  #[binary(default = "default_port")]
+  port: u16,
+  #[binary(default)]
+  tags: Vec<String>,
+}
+
+fn default_port() -> u16 {
+  8080
+}
+
+#[test]
+fn versioned() {
+  let old = vec![PeerV1 { id: 1, name: "a".into() }, PeerV1 { id: 2, name: "b".into() }];
+  let new = vec![PeerV2 { id: 3, name: "c".into(), port: 69, tags: vec!["x".into()] }];
+
+  test_valid(PeerV1 { id: 1, name: "a".into() });
+  test_valid(PeerV2 { id: 3, name: "c".into(), port: 69, tags: vec![] });
+  assert_eq!(new[0].encoded_size(), new[0].to_bytes(ByteEndian::Little).len());
+
+  // newer readers default what older writers didn't know about
+  let bytes = (&old, 69u8).to_bytes(ByteEndian::Little);
+  assert_eq!(
+    <(Vec<PeerV2>, u8)>::from_bytes_exact(&bytes, ByteEndian::Little),
+    Ok((vec![
+      PeerV2 { id: 1, name: "a".into(), port: 8080, tags: vec![] },
+      PeerV2 { id: 2, name: "b".into(), port: 8080, tags: vec![] },
+    ], 69))
+  );
+
+  // only fields marked `default` can be missing
+  let bytes = (1usize, vec![5u8, 0, 0, 0, 1]).to_bytes(ByteEndian::Little);
+  assert_eq!(PeerV1::from_bytes_exact(&bytes, ByteEndian::Little), Err(DecoderError::missing_field("PeerV1", "name")));
+
+  // older readers skip what they don't know about, without losing their place in the input
+  let bytes = (&new, 69u8).to_bytes(ByteEndian::Big);
+  assert_eq!(
+    <(Vec<PeerV1>, u8)>::from_bytes_exact(&bytes, ByteEndian::Big),
+    Ok((vec![PeerV1 { id: 3, name: "c".into() }], 69))
+  );
+
+  // the block is written and read with the settings of the surrounding encoder and decoder
+  let mut encoder = ByteEncoder::new(ByteEndian::Little).with_varint_lengths();
+  new.encode(&mut encoder);
+  let bytes = encoder.into_bytes();
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little).with_varint_lengths();
+  assert_eq!(Vec::<PeerV1>::decode(&mut decoder), Ok(vec![PeerV1 { id: 3, name: "c".into() }]));
+  assert!(decoder.at_end());
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little).with_varint_lengths().with_max_depth(2);
+  assert_eq!(Vec::<PeerV2>::decode(&mut decoder), Err(DecoderError::recursion_limit_exceeded(2)));
+
+  let bytes = new.to_bytes(ByteEndian::Little);
+  let mut slice = [0; 64];
+  let len = new.to_slice(&mut slice, ByteEndian::Little).unwrap();
+  assert_eq!(&slice[..len], bytes);
+  assert_eq!(new.to_writer(vec![], ByteEndian::Little), Ok(bytes.clone()));
+
+  let mut decoder = ReadDecoder::new(&bytes[..], ByteEndian::Little);
+  assert_eq!(Vec::<PeerV1>::decode(&mut decoder), Ok(vec![PeerV1 { id: 3, name: "c".into() }]));
+  assert!(decoder.at_end());
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+#[binary(tag = name)]
+enum Named {
+  Unit,
+  Tuple(u32, u32),
+  Struct { x: u32, y: u32 },
+}
+
+// the string form is the same as the bare one
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+#[binary(tag = "name")]
+enum NamedReordered {
+  Struct { x: u32, y: u32 },
+  Tuple(u32, u32),
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(tag = name, iterative)]
+enum NamedList {
+  Nil,
+  Cons(u8, Box<NamedList>),
+}
+
+#[test]
+fn name_tags() {
+  test_valid(Named::Unit);
+  test_valid(Named::Struct { x: 69, y: 420 });
+  test_valid(NamedList::Cons(1, Box::new(NamedList::Cons(2, Box::new(NamedList::Nil)))));
+
+  let bytes = Named::Tuple(69, 420).to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, ("Tuple", 69u32, 420u32).to_bytes(ByteEndian::Little));
+  assert_eq!(Named::Tuple(69, 420).encoded_size(), bytes.len());
+  assert_eq!(NamedReordered::from_bytes(&bytes, ByteEndian::Little), Ok(NamedReordered::Tuple(69, 420)));
+  assert_eq!(
+    NamedReordered::from_bytes(&Named::Unit.to_bytes(ByteEndian::Little), ByteEndian::Little),
+    Err(DecoderError::invalid_variant_name("NamedReordered", "Unit"))
+  );
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer, SizeHint)]
+#[binary(transparent)]
+struct Meters(f64);
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+#[binary(transparent)]
+struct Newtype<T> {
+  inner: T,
+}
+
+#[test]
+fn transparent() {
+  test_valid(Meters(1.5));
+  test_valid(Newtype { inner: vec![Meters(1.0), Meters(-2.0)] });
+
+  let values = vec![(1u16, String::from("a")), (2, String::from("b"))];
+  let bytes = values.to_bytes(ByteEndian::Big);
+
+  assert_eq!(Newtype { inner: values.clone() }.to_bytes(ByteEndian::Big), bytes);
+  assert_eq!(Newtype::from_bytes_exact(&bytes, ByteEndian::Big), Ok(Newtype { inner: values }));
+  assert_eq!(Meters(1.5).encoded_size(), 8);
+}
\ No newline at end of file
@@ -0,0 +1,353 @@
+use std::collections::{BTreeMap, HashMap};
+
+pub use binary_serializer::prelude::*;
+
+fn encode(f: impl FnOnce(&mut ByteEncoder)) -> Vec<u8> {
+  let mut encoder = ByteEncoder::new(ByteEndian::Little);
+  f(&mut encoder);
+
+  encoder.bytes().clone()
+}
+
+#[test]
+fn varint() {
+  assert_eq!(encode(|e| e.encode_varint_u64(0)), vec![0]);
+  assert_eq!(encode(|e| e.encode_varint_u64(127)), vec![0x7f]);
+  assert_eq!(encode(|e| e.encode_varint_u64(300)), vec![0xac, 0x02]);
+  assert_eq!(encode(|e| e.encode_varint_u64(u64::MAX)).len(), 10);
+
+  for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+    let bytes = encode(|e| e.encode_varint_u64(value));
+    assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).decode_varint_u64(), Ok(value));
+  }
+
+  let invalid: [&[u8]; 3] = [
+    &[0x80, 0x00],
+    &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02],
+    &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x81, 0x00],
+  ];
+
+  for bytes in invalid {
+    assert_eq!(ByteDecoder::new(bytes, ByteEndian::Little).decode_varint_u64(), Err(DecoderError::invalid_varint(64)));
+  }
+
+  assert!(matches!(
+    ByteDecoder::new(&[0x80], ByteEndian::Little).decode_varint_u64(),
+    Err(DecoderError::NotEnoughBytes { .. })
+  ));
+}
+
+#[test]
+fn sorted_ints() {
+  let timestamps = (0..1000u64).map(|idx| 1_650_000_000_000 + idx * 15).collect::<Vec<_>>();
+  let bytes = encode(|e| e.encode_sorted_ints(&timestamps));
+
+  assert!(bytes.len() < timestamps.to_bytes(ByteEndian::Little).len() / 4);
+  assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).decode_sorted_ints(), Ok(timestamps));
+
+  let unsorted = vec![u64::MAX, 0, 69, 42, 420, 0];
+  let bytes = encode(|e| e.encode_sorted_ints(&unsorted));
+
+  assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).decode_sorted_ints(), Ok(unsorted));
+
+  // a bogus count runs out of input instead of reserving memory for it
+  let bytes = encode(|e| e.encode_varint_u64(1 << 30));
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Little);
+
+  assert!(matches!(decoder.decode_sorted_ints(), Err(DecoderError::NotEnoughBytes { .. })));
+  assert_eq!(
+    ByteDecoder::new(&bytes, ByteEndian::Little).with_max_len(1000).decode_sorted_ints(),
+    Err(DecoderError::length_too_large(1 << 30, 1000))
+  );
+}
+
+#[test]
+fn slice_encoder() {
+  let source = (69u32, String::from("hello"), vec![1u16, 2, 3]);
+  let expected = source.to_bytes(ByteEndian::Big);
+
+  let mut bytes = vec![0xff; expected.len()];
+  assert_eq!(source.to_slice(&mut bytes, ByteEndian::Big), Ok(expected.len()));
+  assert_eq!(bytes, expected);
+
+  let mut bytes = [0u8; 64];
+  assert_eq!(source.to_slice(&mut bytes, ByteEndian::Big), Ok(expected.len()));
+  assert_eq!(&bytes[..expected.len()], expected.as_slice());
+
+  let mut bytes = vec![0; expected.len() - 1];
+  assert_eq!(
+    source.to_slice(&mut bytes, ByteEndian::Big),
+    Err(EncoderError::buffer_overflow(expected.len() - 1, expected.len()))
+  );
+}
+
+#[test]
+fn varint_codec() {
+  let source = (1u64, -1i32, u128::MAX, String::from("hello"), vec![300u16, 2, 3], 1.5f32);
+
+  let mut encoder = ByteEncoder::<VarintCodec>::with_codec(ByteEndian::Little);
+  source.encode(&mut encoder);
+  let bytes = encoder.bytes().clone();
+
+  assert!(bytes.len() < source.to_bytes(ByteEndian::Little).len());
+  assert_eq!(&bytes[..6], &[1, 0xff, 0xff, 0xff, 0xff, 0x0f]);
+
+  let mut decoder = ByteDecoder::<VarintCodec>::with_codec(&bytes, ByteEndian::Little);
+  assert_eq!(Deserializer::decode(&mut decoder), Ok(source));
+  assert_eq!(decoder.position(), bytes.len());
+
+  let mut decoder = ByteDecoder::<VarintCodec>::with_codec(&[0x80, 0x02], ByteEndian::Little);
+  assert_eq!(decoder.decode_u8(), Err(DecoderError::invalid_varint(8)));
+}
+
+#[test]
+fn zigzag() {
+  assert_eq!(encode(|e| e.encode_i64_zigzag(0)), vec![0]);
+  assert_eq!(encode(|e| e.encode_i64_zigzag(-1)), vec![1]);
+  assert_eq!(encode(|e| e.encode_i64_zigzag(1)), vec![2]);
+  assert_eq!(encode(|e| e.encode_i8_zigzag(-1)), vec![1]);
+
+  for value in [0, -1, 1, -64, 64, i64::MIN, i64::MAX] {
+    let bytes = encode(|e| e.encode_i64_zigzag(value));
+    assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).decode_i64_zigzag(), Ok(value));
+  }
+
+  for value in [i8::MIN, i8::MAX, -1, 0] {
+    let bytes = encode(|e| e.encode_i8_zigzag(value));
+    assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).decode_i8_zigzag(), Ok(value));
+  }
+
+  let source = (-1i64, i64::MIN, i64::MAX, -1i8, i128::MIN, 200u8);
+
+  let mut encoder = ByteEncoder::<ZigZagCodec>::with_codec(ByteEndian::Little);
+  source.encode(&mut encoder);
+  let bytes = encoder.bytes().clone();
+
+  assert_eq!(bytes[0], 1);
+  assert_eq!(ByteDecoder::<ZigZagCodec>::with_codec(&bytes, ByteEndian::Little).decode_value(), Ok(source));
+}
+
+#[test]
+fn ring_encoder() {
+  let mut encoder = RingEncoder::new(16, ByteEndian::Little);
+
+  assert_eq!(encoder.try_encode(&69u64), Ok(()));
+  assert_eq!(encoder.try_encode(&(1u32, 2u16)), Ok(()));
+  assert_eq!(encoder.try_encode(&420u32), Err(EncoderError::buffer_full(16)));
+  assert_eq!(encoder.len(), 14);
+  assert_eq!(encoder.capacity(), 16);
+
+  let mut out = [0u8; 8];
+  assert_eq!(encoder.read(&mut out), 8);
+  assert_eq!(u64::from_bytes(&out, ByteEndian::Little), Ok(69));
+
+  assert_eq!(encoder.try_encode(&420u32), Ok(()));
+  assert_eq!(encoder.try_encode(&String::from("too long")), Err(EncoderError::buffer_full(16)));
+
+  let mut out = [0u8; 16];
+  assert_eq!(encoder.read(&mut out), 10);
+  assert_eq!(<(u32, u16, u32)>::from_bytes(&out[..10], ByteEndian::Little), Ok((1, 2, 420)));
+  assert!(encoder.is_empty());
+}
+
+/// Accepts `limit` bytes, then fails every write.
+struct Limited {
+  bytes: Vec<u8>,
+  limit: usize,
+}
+
+impl std::io::Write for Limited {
+  fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    if self.bytes.len() + buf.len() > self.limit {
+      return Err(std::io::Error::new(std::io::ErrorKind::WriteZero, "full"));
+    }
+
+    self.bytes.extend_from_slice(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> std::io::Result<()> {
+    Ok(())
+  }
+}
+
+#[test]
+fn write_encoder() {
+  let source = (69u32, String::from("hello"), vec![1u16, 2, 3], -1.5f64);
+  let expected = source.to_bytes(ByteEndian::Big);
+
+  assert_eq!(source.to_writer(Vec::new(), ByteEndian::Big), Ok(expected.clone()));
+
+  let writer = source.to_writer(Limited { bytes: Vec::new(), limit: expected.len() }, ByteEndian::Big).unwrap();
+  assert_eq!(writer.bytes, expected);
+
+  assert!(matches!(
+    source.to_writer(Limited { bytes: Vec::new(), limit: 10 }, ByteEndian::Big),
+    Err(EncoderError::Io { kind: std::io::ErrorKind::WriteZero, .. })
+  ));
+}
+
+#[test]
+fn primitive_fast_path() {
+  let ints = (0..16384u64).map(|idx| idx.wrapping_mul(11400714819323198485)).collect::<Vec<_>>();
+  let floats = vec![1.5f32, -0.0, f32::MAX, f32::NAN];
+  let source = (ints, floats, vec![-1i16, 420], vec![true, false]);
+
+  // `WriteEncoder` always encodes element by element
+  assert_eq!(source.to_writer(Vec::new(), ByteEndian::Native), Ok(source.to_bytes(ByteEndian::Native)));
+}
+
+#[test]
+fn varint_lengths() {
+  let source = (vec![vec![7u8, 8]; 3], vec![69usize, 420], HashMap::from([(1u8, 2u8)]));
+
+  let mut encoder = ByteEncoder::new(ByteEndian::Big).with_varint_lengths();
+  source.encode(&mut encoder);
+
+  let bytes = encoder.bytes().clone();
+  assert_eq!(bytes.len(), 1 + 3 * (1 + 2) + 1 + 1 + 2 + 1 + 2);
+  assert!(bytes.len() < source.to_bytes(ByteEndian::Big).len());
+
+  let mut decoder = ByteDecoder::new(&bytes, ByteEndian::Big).with_varint_lengths();
+  assert_eq!(decoder.peek_len(), Ok(3));
+  assert_eq!(decoder.decode_value(), Ok(source));
+
+  let mut decoder = ByteDecoder::new(&[0x80, 0x80], ByteEndian::Big).with_varint_lengths();
+  assert!(matches!(decoder.decode_value::<Vec<u8>>(), Err(DecoderError::NotEnoughBytes { .. })));
+
+  let mut decoder = ByteDecoder::new(&[0x81, 0x00], ByteEndian::Big).with_varint_lengths();
+  assert_eq!(decoder.decode_value::<Vec<u8>>(), Err(DecoderError::invalid_varint(64)));
+}
+
+#[test]
+fn varint_i64() {
+  assert_eq!(encode(|e| [0, -1, 1, -2, 2].into_iter().for_each(|it| e.encode_varint_i64(it))), vec![0, 1, 2, 3, 4]);
+  assert_eq!(encode(|e| e.encode_varint_i64(i64::MIN)), encode(|e| e.encode_varint_u64(u64::MAX)));
+
+  for value in [0, -1, 63, -64, 64, i32::MIN as i64, i64::MAX, i64::MIN] {
+    let bytes = encode(|e| e.encode_varint_i64(value));
+    assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).decode_varint_i64(), Ok(value));
+  }
+
+  // one bit past `u64::MAX`
+  let bytes = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x03];
+  assert_eq!(ByteDecoder::new(&bytes, ByteEndian::Little).decode_varint_i64(), Err(DecoderError::invalid_varint(64)));
+}
+
+#[test]
+fn sorted_maps() {
+  let map = (0..100u32).map(|idx| (idx * 7919 % 1000, idx.to_string())).collect::<HashMap<_, _>>();
+  let sorted = map.clone().into_iter().collect::<BTreeMap<_, _>>();
+
+  assert_eq!(encode(|e| e.encode_map_sorted(&map)), sorted.to_bytes(ByteEndian::Little));
+
+  let mut encoder = ByteEncoder::new(ByteEndian::Big).with_sorted_maps();
+  map.encode(&mut encoder);
+
+  // big endian keys compare the same way as their values
+  assert_eq!(encoder.bytes(), &sorted.to_bytes(ByteEndian::Big));
+  assert_eq!(HashMap::from_bytes(encoder.bytes(), ByteEndian::Big), Ok(map));
+}
+
+#[test]
+fn byte_blobs() {
+  let blob = (0..=255u8).cycle().take(100_000).collect::<Vec<_>>();
+  let bytes = blob.to_bytes(ByteEndian::Big);
+
+  assert_eq!(&bytes[..8], &100_000u64.to_be_bytes());
+  assert_eq!(&bytes[8..], blob.as_slice());
+  assert_eq!(blob.as_slice().to_bytes(ByteEndian::Big), bytes);
+  assert_eq!(blob.to_writer(Vec::new(), ByteEndian::Big), Ok(bytes.clone()));
+
+  assert_eq!(Vec::<u8>::from_bytes(&bytes, ByteEndian::Big), Ok(blob.clone()));
+  assert_eq!(Vec::<u8>::from_reader(bytes.as_slice(), ByteEndian::Big), Ok(blob.clone()));
+  assert!(Vec::<u8>::from_reader(&bytes[..50_000], ByteEndian::Big).is_err());
+
+  // varint codecs don't write a `u8` as is, those still go byte by byte
+  let mut encoder = ByteEncoder::<VarintCodec>::with_codec(ByteEndian::Little);
+  encoder.encode_value(&vec![1u8, 200]);
+
+  assert_eq!(encoder.bytes(), &vec![2, 1, 0xc8, 0x01]);
+  assert_eq!(ByteDecoder::<VarintCodec>::with_codec(encoder.bytes(), ByteEndian::Little).decode_value(), Ok(vec![1u8, 200]));
+}
+
+#[test]
+fn reuse_buffer() {
+  let mut buffer = Vec::with_capacity(64);
+
+  for idx in 0..100u32 {
+    buffer.clear();
+    encode_into(&(idx, "record"), &mut buffer, ByteEndian::Little);
+
+    assert_eq!(buffer, (idx, "record").to_bytes(ByteEndian::Little));
+  }
+
+  // nothing past the first record needed more room
+  assert_eq!(buffer.capacity(), 64);
+
+  encode_into(&1u8, &mut buffer, ByteEndian::Little);
+  assert_eq!(buffer.last(), Some(&1));
+
+  let mut encoder = ByteEncoder::with_buffer(vec![0xff], ByteEndian::Big);
+  encoder.encode_u16(1);
+
+  assert_eq!(encoder.into_bytes(), vec![0xff, 0, 1]);
+}
+
+/// Forces network order for the port, the rest follows the caller.
+struct Endpoint {
+  id: u32,
+  port: u16,
+}
+
+impl Serializer for Endpoint {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u32(self.id);
+
+    let endian = encoder.endian();
+    encoder.set_endian(ByteEndian::Big);
+    encoder.encode_u16(self.port);
+    encoder.set_endian(endian);
+  }
+}
+
+#[test]
+fn endian() {
+  let endpoint = Endpoint { id: 1, port: 0x1f90 };
+
+  assert_eq!(endpoint.to_bytes(ByteEndian::Little), vec![1, 0, 0, 0, 0x1f, 0x90]);
+  assert_eq!(ByteEncoder::new(ByteEndian::Little).endian().is_native(), cfg!(target_endian = "little"));
+  assert!(matches!(SliceEncoder::new(&mut [], ByteEndian::Big).endian(), ByteEndian::Big));
+  assert!(matches!(ByteDecoder::new(&[], ByteEndian::Native).endian(), ByteEndian::Native));
+
+  let mut counter = Counter(0);
+  endpoint.encode(&mut counter);
+
+  assert_eq!(counter.0, 6);
+  assert!(matches!(counter.endian(), ByteEndian::Native));
+}
+
+/// Only counts bytes, has no endianness of its own.
+struct Counter(usize);
+
+impl Encoder for Counter {
+  fn encode_u8(&mut self, _: u8) { self.0 += 1; }
+  fn encode_u16(&mut self, _: u16) { self.0 += 2; }
+  fn encode_u32(&mut self, _: u32) { self.0 += 4; }
+  fn encode_u64(&mut self, _: u64) { self.0 += 8; }
+  fn encode_u128(&mut self, _: u128) { self.0 += 16; }
+
+  fn encode_i8(&mut self, _: i8) { self.0 += 1; }
+  fn encode_i16(&mut self, _: i16) { self.0 += 2; }
+  fn encode_i32(&mut self, _: i32) { self.0 += 4; }
+  fn encode_i64(&mut self, _: i64) { self.0 += 8; }
+  fn encode_i128(&mut self, _: i128) { self.0 += 16; }
+
+  fn encode_f32(&mut self, _: f32) { self.0 += 4; }
+  fn encode_f64(&mut self, _: f64) { self.0 += 8; }
+
+  fn encode_block(&mut self, f: impl FnOnce(&mut Self)) {
+    self.encode_usize(0);
+    f(self);
+  }
+}
\ No newline at end of file
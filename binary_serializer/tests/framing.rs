@@ -0,0 +1,35 @@
+use std::io::Cursor;
+
+use binary_serializer::framing::{read_framed, write_framed};
+use binary_serializer::prelude::*;
+
+#[test]
+fn round_trip() {
+  let mut stream = Vec::new();
+
+  write_framed(&(1u32, String::from("first")), &mut stream, ByteEndian::Big).unwrap();
+  write_framed(&(2u32, String::from("second")), &mut stream, ByteEndian::Big).unwrap();
+  write_framed(&(3u32, String::new()), &mut stream, ByteEndian::Big).unwrap();
+
+  assert_eq!(&stream[..4], &((1u32, String::from("first")).to_bytes(ByteEndian::Big).len() as u32).to_be_bytes());
+
+  let mut cursor = Cursor::new(stream);
+
+  assert_eq!(read_framed(&mut cursor, ByteEndian::Big), Ok((1u32, String::from("first"))));
+  assert_eq!(read_framed(&mut cursor, ByteEndian::Big), Ok((2u32, String::from("second"))));
+  assert_eq!(read_framed(&mut cursor, ByteEndian::Big), Ok((3u32, String::new())));
+  assert_eq!(read_framed::<(u32, String), _>(&mut cursor, ByteEndian::Big), Err(DecoderError::not_enough_bytes("u32", 0)));
+}
+
+#[test]
+fn boundaries() {
+  let mut stream = Vec::new();
+  write_framed(&(1u32, 2u32), &mut stream, ByteEndian::Little).unwrap();
+
+  // a frame holding more than the value is rejected instead of leaking into the next one
+  assert_eq!(read_framed::<u32, _>(stream.as_slice(), ByteEndian::Little), Err(DecoderError::trailing_bytes(4)));
+
+  // a length that runs past the end of the stream
+  stream[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+  assert!(matches!(read_framed::<(u32, u32), _>(stream.as_slice(), ByteEndian::Little), Err(DecoderError::NotEnoughBytes { .. })));
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+#![cfg(feature = "checksum")]
+
+use binary_serializer::integrity::{from_bytes_checked, to_bytes_checked, ChecksummedDecoder, ChecksummedEncoder};
+use binary_serializer::prelude::*;
+
+#[test]
+fn round_trip() {
+  let source = (69u32, String::from("hello"), vec![1.5f64, -2.0]);
+  let bytes = to_bytes_checked(&source, ByteEndian::Big).unwrap();
+
+  assert_eq!(&bytes[..bytes.len() - 4], source.to_bytes(ByteEndian::Big).as_slice());
+  assert_eq!(from_bytes_checked(&bytes, ByteEndian::Big), Ok(source));
+
+  let mut encoder = ChecksummedEncoder::new(ByteEndian::Little);
+  encoder.encode_value(&1u8);
+  encoder.encoder().encode_u16(2);
+
+  let bytes = encoder.finish().unwrap();
+  let mut decoder = ChecksummedDecoder::new(&bytes, ByteEndian::Little).unwrap();
+
+  assert_eq!(decoder.decoder().decode_value::<(u8, u16)>(), Ok((1, 2)));
+  assert_eq!(decoder.decoder().remaining(), 0);
+}
+
+#[test]
+fn corruption() {
+  let mut bytes = to_bytes_checked(&(1u64, 2u64), ByteEndian::Little).unwrap();
+  bytes[3] ^= 0x10;
+
+  assert!(matches!(from_bytes_checked::<(u64, u64)>(&bytes, ByteEndian::Little), Err(DecoderError::ChecksumMismatch { .. })));
+  assert_eq!(from_bytes_checked::<u8>(&[1, 2], ByteEndian::Little), Err(DecoderError::not_enough_bytes("u32", 2)));
+
+  // a valid checksum over more than the value
+  let bytes = to_bytes_checked(&(1u64, 2u64), ByteEndian::Little).unwrap();
+  assert_eq!(from_bytes_checked::<u64>(&bytes, ByteEndian::Little), Err(DecoderError::trailing_bytes(8)));
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+use binary_serializer::migrate::MigrationRegistry;
+
+pub use binary_serializer::prelude::*;
+
+#[derive(Serializer, Deserializer)]
+struct V1 {
+  name: String,
+}
+
+#[derive(Serializer, Deserializer)]
+struct V2 {
+  name: String,
+  age: u8,
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct V3 {
+  first: String,
+  last: String,
+  age: u32,
+}
+
+fn registry() -> MigrationRegistry<V3> {
+  MigrationRegistry::new(3)
+    .register(1, 2, |v1: V1| V2 { name: v1.name, age: 0 })
+    .register(2, 3, |v2: V2| {
+      let (first, last) = v2.name.split_once(' ').unwrap_or((&v2.name, ""));
+
+      V3 { first: first.to_string(), last: last.to_string(), age: v2.age as u32 }
+    })
+}
+
+#[test]
+fn chain() {
+  let registry = registry();
+  let v1 = (1u32, V1 { name: String::from("Jane Doe") }).to_bytes(ByteEndian::Little);
+  let v2 = (2u32, V2 { name: String::from("John"), age: 69 }).to_bytes(ByteEndian::Little);
+
+  assert_eq!(
+    registry.from_bytes_migrated(&v1, ByteEndian::Little),
+    Ok(V3 { first: String::from("Jane"), last: String::from("Doe"), age: 0 })
+  );
+  assert_eq!(
+    registry.from_bytes_migrated(&v2, ByteEndian::Little),
+    Ok(V3 { first: String::from("John"), last: String::new(), age: 69 })
+  );
+
+  let v3 = V3 { first: String::from("a"), last: String::from("b"), age: 420 };
+  let bytes = registry.to_bytes(&v3, ByteEndian::Little).unwrap();
+
+  assert_eq!(registry.from_bytes_migrated(&bytes, ByteEndian::Little), Ok(v3));
+}
+
+#[test]
+fn unknown_version() {
+  let bytes = (0u32, V1 { name: String::new() }).to_bytes(ByteEndian::Little);
+
+  assert_eq!(registry().from_bytes_migrated(&bytes, ByteEndian::Little), Err(DecoderError::unknown_version(0)));
+}
\ No newline at end of file
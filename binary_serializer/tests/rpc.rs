@@ -0,0 +1,51 @@
+use binary_serializer::rpc::RpcTable;
+pub use binary_serializer::prelude::*;
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Ping(u64);
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Pong(u64);
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Get {
+  key: String,
+}
+
+#[test]
+fn round_trip() {
+  let table = RpcTable::new()
+    .register::<Ping, Pong>(1)
+    .register::<Get, Vec<u8>>(2);
+
+  let bytes = table.encode_request(1, &Ping(69), ByteEndian::Little).unwrap();
+  let (opcode, request) = table.dispatch_request(&bytes, ByteEndian::Little).unwrap();
+  assert_eq!(opcode, 1);
+  assert_eq!(request.downcast_ref::<Ping>(), Some(&Ping(69)));
+
+  let bytes = table.encode_response(1, &Pong(420), ByteEndian::Little).unwrap();
+  let (_, response) = table.dispatch_response(&bytes, ByteEndian::Little).unwrap();
+  assert_eq!(response.downcast_ref::<Pong>(), Some(&Pong(420)));
+
+  let get = Get { key: String::from("key") };
+  let bytes = table.encode_request(2, &get, ByteEndian::Big).unwrap();
+  let (opcode, request) = table.dispatch_request(&bytes, ByteEndian::Big).unwrap();
+  assert_eq!(opcode, 2);
+  assert_eq!(request.downcast_ref::<Get>(), Some(&get));
+
+  let bytes = table.encode_response(2, &vec![1u8, 2, 3], ByteEndian::Big).unwrap();
+  let (_, response) = table.dispatch_response(&bytes, ByteEndian::Big).unwrap();
+  assert_eq!(response.downcast_ref::<Vec<u8>>(), Some(&vec![1, 2, 3]));
+}
+
+#[test]
+fn mismatch() {
+  let table = RpcTable::new().register::<Ping, Pong>(1);
+
+  assert!(table.encode_request(1, &Pong(0), ByteEndian::Little).is_none());
+  assert!(table.encode_request(2, &Ping(0), ByteEndian::Little).is_none());
+  assert!(matches!(
+    table.dispatch_request(&3u16.to_bytes(ByteEndian::Little), ByteEndian::Little),
+    Err(DecoderError::UnknownOpcode { opcode: 3 })
+  ));
+}
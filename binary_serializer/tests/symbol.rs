@@ -0,0 +1,28 @@
+use binary_serializer::symbol::{Symbol, SymbolTable};
+
+pub use binary_serializer::prelude::*;
+
+#[test]
+fn round_trip() {
+  let mut table = SymbolTable::new();
+  let names = ["x", "y", "x", "len", "y"].map(|name| table.intern(name));
+
+  assert_eq!(names, [Symbol(0), Symbol(1), Symbol(0), Symbol(2), Symbol(1)]);
+  assert_eq!(table.len(), 3);
+  assert_eq!(names.to_bytes(ByteEndian::Little).len(), 5 * 4);
+
+  let bytes = (table.clone(), names.to_vec()).to_bytes(ByteEndian::Little);
+  let (decoded, symbols) = <(SymbolTable, Vec<Symbol>)>::from_bytes(&bytes, ByteEndian::Little).unwrap();
+
+  assert_eq!(decoded, table);
+  assert_eq!(symbols.iter().map(|&it| decoded.resolve(it)).collect::<Vec<_>>(), [Some("x"), Some("y"), Some("x"), Some("len"), Some("y")]);
+  assert_eq!(decoded.get("len"), Some(Symbol(2)));
+  assert_eq!(decoded.resolve(Symbol(3)), None);
+}
+
+#[test]
+fn duplicate() {
+  let bytes = vec![String::from("x"), String::from("x")].to_bytes(ByteEndian::Little);
+
+  assert_eq!(SymbolTable::from_bytes(&bytes, ByteEndian::Little), Err(DecoderError::custom("symbol `x` is interned twice")));
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+#![cfg(feature = "testing")]
+
+use std::collections::BTreeMap;
+
+use binary_serializer::prelude::*;
+use binary_serializer::testing::assert_roundtrip;
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+enum Shape {
+  Circle { radius: f32 },
+  Polygon(Vec<(i16, i16)>),
+}
+
+#[test]
+fn roundtrip() {
+  assert_roundtrip(Shape::Circle { radius: 1.5 });
+  assert_roundtrip(Shape::Polygon(vec![(0, 0), (-1, 2), (3, -4)]));
+  assert_roundtrip(BTreeMap::from([(1u8, String::from("one")), (2, String::from("two"))]));
+}
+
+/// Decodes to a value equal to the original, but not from all of its bytes.
+#[derive(Debug, PartialEq)]
+struct Lossy(u8);
+
+impl Serializer for Lossy {
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u8(self.0);
+    encoder.encode_u8(0);
+  }
+}
+
+impl Deserializer for Lossy {
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    decoder.decode_u8().map(Lossy)
+  }
+}
+
+#[test]
+#[should_panic(expected = "decoding Little endian")]
+fn trailing_bytes() {
+  assert_roundtrip(Lossy(1));
+}
\ No newline at end of file
@@ -0,0 +1,27 @@
+use binary_serializer::transcode::{transcode, transcode_in_place, Schema};
+pub use binary_serializer::prelude::*;
+
+#[test]
+fn primitive_slice() {
+  let source = vec![69u32, 420, u32::MAX, 0];
+  let big = source.to_bytes(ByteEndian::Big);
+  let little = transcode(&big, ByteEndian::Big, ByteEndian::Little, Schema::PrimitiveSlice { width: 4 }).unwrap();
+
+  assert_eq!(little, source.to_bytes(ByteEndian::Little));
+
+  let source = vec![0.5f64, -1.25, f64::MAX];
+  let mut bytes = source.to_bytes(ByteEndian::Little);
+  transcode_in_place(&mut bytes, ByteEndian::Little, ByteEndian::Big, Schema::PrimitiveSlice { width: 8 }).unwrap();
+
+  assert_eq!(Vec::<f64>::from_bytes(&bytes, ByteEndian::Big), Ok(source));
+}
+
+#[test]
+fn truncated() {
+  let mut bytes = vec![1u16, 2, 3].to_bytes(ByteEndian::Big);
+  bytes.pop();
+  let copy = bytes.clone();
+
+  assert!(transcode_in_place(&mut bytes, ByteEndian::Big, ByteEndian::Little, Schema::PrimitiveSlice { width: 2 }).is_err());
+  assert_eq!(bytes, copy);
+}
@@ -0,0 +1,360 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::hash::{BuildHasherDefault, Hasher};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, SocketAddrV6};
+use std::num::{NonZeroI64, NonZeroU8, NonZeroU16, NonZeroU32};
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI16, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+pub use binary_serializer::prelude::*;
+
+#[test]
+fn weak() {
+  let strong = Rc::new(69u32);
+  let weak = Rc::downgrade(&strong);
+
+  assert_eq!(weak.to_bytes(ByteEndian::Little), vec![0]);
+
+  let parsed = Weak::<u32>::from_bytes(&[0], ByteEndian::Little).unwrap();
+
+  assert!(parsed.upgrade().is_none());
+  assert!(Weak::<u32>::from_bytes(&[1], ByteEndian::Little).is_err());
+}
+
+#[test]
+fn duration() {
+  let source = Duration::new(69, 420_000_000);
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes.len(), 12);
+  assert_eq!(Duration::WIRE_SIZE, 12);
+  assert_eq!(source.encoded_size(), 12);
+  assert_eq!(Duration::from_bytes(&bytes, ByteEndian::Little), Ok(source));
+  assert_eq!(Duration::from_bytes(&Duration::MAX.to_bytes(ByteEndian::Big), ByteEndian::Big), Ok(Duration::MAX));
+
+  let bytes = (69u64, u32::MAX).to_bytes(ByteEndian::Little);
+
+  assert_eq!(Duration::from_bytes(&bytes, ByteEndian::Little), Err(DecoderError::invalid_duration(u32::MAX)));
+}
+
+#[test]
+fn option() {
+  let source = (Some(String::from("hello")), None::<u32>, Some(69u32), vec![None, Some(1u8)]);
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(<_>::from_bytes(&bytes, ByteEndian::Little), Ok(source));
+  assert_eq!(None::<u64>.to_bytes(ByteEndian::Little), vec![0]);
+  assert_eq!(Some(69u8).to_bytes(ByteEndian::Little), vec![1, 69]);
+
+  assert_eq!(
+    Option::<u8>::from_bytes(&[2, 69], ByteEndian::Little),
+    Err(DecoderError::invalid_variant("core::option::Option<u8>", 2, 2))
+  );
+}
+
+#[derive(Default)]
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+  fn finish(&self) -> u64 {
+    self.0
+  }
+
+  fn write(&mut self, bytes: &[u8]) {
+    for &byte in bytes {
+      self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+    }
+  }
+}
+
+type FnvBuildHasher = BuildHasherDefault<Fnv>;
+
+#[test]
+fn custom_hasher() {
+  let map = (0..100u32).map(|idx| (idx, idx.to_string())).collect::<HashMap<_, _, FnvBuildHasher>>();
+  let bytes = map.to_bytes(ByteEndian::Little);
+
+  assert_eq!(HashMap::<u32, String, FnvBuildHasher>::from_bytes(&bytes, ByteEndian::Little), Ok(map.clone()));
+  assert_eq!(HashMap::<u32, String>::from_bytes(&bytes, ByteEndian::Little), Ok(map.into_iter().collect()));
+
+  let set = (0..100u64).map(|idx| idx * 3).collect::<HashSet<_, FnvBuildHasher>>();
+  let bytes = set.to_bytes(ByteEndian::Big);
+
+  assert_eq!(HashSet::<u64, FnvBuildHasher>::from_bytes(&bytes, ByteEndian::Big), Ok(set.clone()));
+  assert_eq!(bytes.len(), 8 + set.len() * 8);
+}
+
+#[test]
+fn array() {
+  let hash = [0xabu8; 32];
+  let bytes = hash.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes.len(), 32);
+  assert_eq!(<[u8; 32]>::from_bytes(&bytes, ByteEndian::Little), Ok(hash));
+
+  let source = [String::from("a"), String::from("bc")];
+  assert_eq!(<[String; 2]>::from_bytes(&source.to_bytes(ByteEndian::Big), ByteEndian::Big), Ok(source));
+  assert_eq!(<[u64; 0]>::from_bytes(&[], ByteEndian::Big), Ok([]));
+
+  assert!(matches!(
+    <[u16; 4]>::from_bytes(&[1, 2, 3, 4, 5, 6], ByteEndian::Big),
+    Err(DecoderError::NotEnoughBytes { index: 6, .. })
+  ));
+}
+
+#[cfg(not(feature = "utf16_strings"))]
+#[test]
+fn string() {
+  let source = String::from("hello, wörld");
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes.len(), 8 + source.len());
+  assert_eq!(&bytes[8..], source.as_bytes());
+  assert_eq!(String::from_bytes(&bytes, ByteEndian::Little), Ok(source));
+
+  let bytes = vec![b'a', b'b', 0xff, b'c'].to_bytes(ByteEndian::Little);
+  assert_eq!(String::from_bytes(&bytes, ByteEndian::Little), Err(DecoderError::invalid_utf8(10)));
+}
+
+#[test]
+fn native_endian() {
+  let source = (69u32, -1.5f64, vec![420u16]);
+  let native = if cfg!(target_endian = "little") { ByteEndian::Little } else { ByteEndian::Big };
+  let bytes = source.to_bytes(ByteEndian::Native);
+
+  assert_eq!(bytes, source.to_bytes(native));
+  assert_eq!(<_>::from_bytes(&bytes, ByteEndian::Native), Ok(source));
+  assert!(ByteEndian::Native.is_native());
+}
+
+#[derive(Debug, PartialEq)]
+struct Packed(u16, u16);
+
+// claims to be primitive without providing the bulk copies, falls back to one value at a time
+impl Serializer for Packed {
+  const IS_PRIMITIVE: bool = true;
+
+  fn encode(&self, encoder: &mut impl Encoder) {
+    encoder.encode_u16(self.0);
+    encoder.encode_u16(self.1);
+  }
+}
+
+impl Deserializer for Packed {
+  const IS_PRIMITIVE: bool = true;
+
+  fn decode(decoder: &mut impl Decoder) -> DecoderResult<Self> {
+    Ok(Self(decoder.decode_u16()?, decoder.decode_u16()?))
+  }
+}
+
+#[test]
+fn primitive_fallback() {
+  let source = vec![Packed(1, 2), Packed(69, 420)];
+  let bytes = source.to_bytes(ByteEndian::Native);
+
+  assert_eq!(bytes, vec![(1u16, 2u16), (69, 420)].to_bytes(ByteEndian::Native));
+  assert_eq!(Vec::<Packed>::from_bytes(&bytes, ByteEndian::Native), Ok(source));
+}
+
+#[test]
+fn char() {
+  let source = vec!['a', 'ñ', '🦀', '\0', char::MAX];
+  let bytes = source.to_bytes(ByteEndian::Big);
+
+  assert_eq!(bytes.len(), 8 + 4 * source.len());
+  assert_eq!(Vec::<char>::from_bytes(&bytes, ByteEndian::Big), Ok(source));
+
+  for value in [0xd800u32, 0xdfff, 0x110000, u32::MAX] {
+    assert_eq!(char::from_bytes(&value.to_bytes(ByteEndian::Big), ByteEndian::Big), Err(DecoderError::invalid_char(value)));
+  }
+}
+
+#[test]
+fn non_zero() {
+  let source = (NonZeroU32::new(69).unwrap(), NonZeroI64::new(-420).unwrap(), Some(NonZeroU8::MAX));
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, (69u32, -420i64, Some(255u8)).to_bytes(ByteEndian::Little));
+  assert_eq!(<_>::from_bytes(&bytes, ByteEndian::Little), Ok(source));
+
+  assert_eq!(
+    NonZeroU16::from_bytes(&0u16.to_bytes(ByteEndian::Little), ByteEndian::Little),
+    Err(DecoderError::unexpected_zero(std::any::type_name::<NonZeroU16>()))
+  );
+}
+
+#[test]
+fn btree() {
+  let map = BTreeMap::from([(3u8, String::from("c")), (1, String::from("a")), (2, String::from("b"))]);
+  let set = BTreeSet::from([69u32, 1, 420]);
+  let bytes = (map.clone(), set.clone()).to_bytes(ByteEndian::Little);
+
+  let sorted = vec![(1u8, String::from("a")), (2, String::from("b")), (3, String::from("c"))];
+  assert_eq!(bytes, (sorted, vec![1u32, 69, 420]).to_bytes(ByteEndian::Little));
+  assert_eq!(<_>::from_bytes(&bytes, ByteEndian::Little), Ok((map.clone(), set)));
+
+  let hash_map = map.clone().into_iter().collect::<HashMap<_, _>>();
+  assert_eq!(BTreeMap::from_bytes(&hash_map.to_bytes(ByteEndian::Little), ByteEndian::Little), Ok(map));
+}
+
+#[test]
+fn pointers() {
+  let shared = Arc::<str>::from("shared");
+  let source = (Rc::new(69u32), vec![shared.clone(), shared], Box::<str>::from("boxed"));
+  let bytes = source.to_bytes(ByteEndian::Big);
+
+  assert_eq!(bytes, (69u32, vec!["shared"; 2], "boxed").to_bytes(ByteEndian::Big));
+
+  let decoded = <(Rc<u32>, Vec<Arc<str>>, Box<str>)>::from_bytes(&bytes, ByteEndian::Big).unwrap();
+  assert_eq!(decoded, source);
+  assert!(!Arc::ptr_eq(&decoded.1[0], &decoded.1[1]));
+}
+
+#[test]
+fn result() {
+  let source: (Result<u16, String>, Result<u16, String>) = (Ok(69), Err(String::from("failed")));
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes[..3], [0, 69, 0]);
+  assert_eq!(bytes[3], 1);
+  assert_eq!(<_>::from_bytes(&bytes, ByteEndian::Little), Ok(source));
+
+  assert_eq!(
+    Result::<u16, String>::from_bytes(&[2, 69, 0], ByteEndian::Little),
+    Err(DecoderError::invalid_variant(std::any::type_name::<Result<u16, String>>(), 2, 2))
+  );
+}
+
+#[test]
+fn net() {
+  let v4 = SocketAddr::from(([127, 0, 0, 1], 8080));
+  let v6 = SocketAddr::V6(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 7, 42));
+
+  for endian in [ByteEndian::Little, ByteEndian::Big] {
+    for addr in [v4, v6] {
+      let bytes = addr.to_bytes(endian);
+
+      assert_eq!(bytes.len(), addr.encoded_size());
+      assert_eq!(SocketAddr::from_bytes(&bytes, endian), Ok(addr));
+      assert_eq!(IpAddr::from_bytes(&addr.ip().to_bytes(endian), endian), Ok(addr.ip()));
+    }
+  }
+
+  // octets in order and the port in network order, whatever the endianness
+  assert_eq!(v4.to_bytes(ByteEndian::Little), vec![0, 127, 0, 0, 1, 0x1f, 0x90]);
+  assert_eq!(SocketAddrV6::WIRE_SIZE, 26);
+
+  assert!(matches!(IpAddr::from_bytes(&[2], ByteEndian::Little), Err(DecoderError::InvalidVariant { index: 2, count: 2, .. })));
+}
+
+#[test]
+fn tuples() {
+  assert_eq!(().to_bytes(ByteEndian::Little), Vec::<u8>::new());
+  assert_eq!(<()>::from_bytes_exact(&[], ByteEndian::Little), Ok(()));
+  assert_eq!(<()>::WIRE_SIZE, 0);
+
+  assert_eq!((69u32,).to_bytes(ByteEndian::Big), 69u32.to_bytes(ByteEndian::Big));
+  assert_eq!(<(u32,)>::from_bytes(&[0, 0, 0, 69], ByteEndian::Big), Ok((69,)));
+
+  let source = (1u8, 2u16, 3u32, 4u64, 5i8, 6i16, 7i32, 8i64, true, 'x', (), (0u8,), 13u8, 14u16, 15u32, String::from("16"));
+  let bytes = source.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes.len(), source.encoded_size());
+
+  // std only compares tuples of up to 12 elements
+  let decoded = <(u8, u16, u32, u64, i8, i16, i32, i64, bool, char, (), (u8,), u8, u16, u32, String)>::from_bytes(&bytes, ByteEndian::Little).unwrap();
+  assert_eq!(decoded.to_bytes(ByteEndian::Little), bytes);
+  assert_eq!((decoded.9, decoded.15), ('x', String::from("16")));
+}
+
+#[test]
+fn sequences() {
+  let mut deque = VecDeque::from(vec![3u32, 4]);
+  deque.push_front(2);
+  deque.push_front(1);
+
+  let bytes = deque.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, vec![1u32, 2, 3, 4].to_bytes(ByteEndian::Little));
+  assert_eq!(bytes.len(), deque.encoded_size());
+  assert_eq!(VecDeque::from_bytes(&bytes, ByteEndian::Little), Ok(deque));
+  assert_eq!(LinkedList::from_bytes(&bytes, ByteEndian::Little), Ok(LinkedList::from([1u32, 2, 3, 4])));
+
+  let heap = BinaryHeap::from(vec![5u8, 1, 8, 3]);
+  let decoded = BinaryHeap::<u8>::from_bytes(&heap.to_bytes(ByteEndian::Big), ByteEndian::Big).unwrap();
+
+  assert_eq!(decoded.into_sorted_vec(), vec![1, 3, 5, 8]);
+  assert_eq!(BinaryHeap::<u8>::from_bytes(&vec![1u8, 9, 4].to_bytes(ByteEndian::Big), ByteEndian::Big).unwrap().peek(), Some(&9));
+}
+
+#[derive(Debug, PartialEq, Serializer, Deserializer)]
+struct Config {
+  name: Cow<'static, str>,
+  ports: Cow<'static, [u16]>,
+}
+
+#[test]
+fn cow() {
+  const PORTS: &[u16] = &[80, 443];
+
+  let borrowed = Config { name: Cow::Borrowed("default"), ports: Cow::Borrowed(PORTS) };
+  let owned = Config { name: Cow::Owned(String::from("default")), ports: Cow::Owned(PORTS.to_vec()) };
+  let bytes = borrowed.to_bytes(ByteEndian::Little);
+
+  assert_eq!(bytes, owned.to_bytes(ByteEndian::Little));
+  assert_eq!(bytes, ("default", PORTS).to_bytes(ByteEndian::Little));
+
+  let decoded = Config::from_bytes(&bytes, ByteEndian::Little).unwrap();
+
+  assert!(matches!((&decoded.name, &decoded.ports), (Cow::Owned(_), Cow::Owned(_))));
+  assert_eq!(decoded, borrowed);
+}
+
+#[test]
+fn atomics() {
+  let counters = (AtomicU64::new(69), AtomicUsize::new(420), AtomicI16::new(-1), AtomicBool::new(true));
+  counters.0.fetch_add(1, Ordering::Relaxed);
+
+  let bytes = counters.to_bytes(ByteEndian::Little);
+  assert_eq!(bytes, (70u64, 420usize, -1i16, true).to_bytes(ByteEndian::Little));
+
+  let (a, b, c, d) = <(AtomicU64, AtomicUsize, AtomicI16, AtomicBool)>::from_bytes(&bytes, ByteEndian::Little).unwrap();
+  assert_eq!((a.into_inner(), b.into_inner(), c.into_inner(), d.into_inner()), (70, 420, -1, true));
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn f16() {
+  use half::f16;
+
+  let values = vec![f16::from_f32(1.5), f16::NEG_INFINITY, f16::MIN_POSITIVE, f16::from_f32(-0.0)];
+
+  assert_eq!(f16::from_f32(1.5).to_bytes(ByteEndian::Big), 0x3e00u16.to_bytes(ByteEndian::Big));
+  assert_eq!(values.encoded_size(), 8 + 2 * values.len());
+
+  for endian in [ByteEndian::Little, ByteEndian::Big, ByteEndian::Native] {
+    let bytes = values.to_bytes(endian);
+
+    assert_eq!(bytes.len(), values.encoded_size());
+    assert_eq!(Vec::<f16>::from_bytes(&bytes, endian), Ok(values.clone()));
+  }
+}
+
+#[test]
+fn ranges() {
+  let range = 10u64..20;
+  let inclusive = 1u32..=5;
+
+  assert_eq!(range.to_bytes(ByteEndian::Big), (10u64, 20u64).to_bytes(ByteEndian::Big));
+  assert_eq!(inclusive.to_bytes(ByteEndian::Little), (1u32, 5u32).to_bytes(ByteEndian::Little));
+  assert_eq!(<std::ops::Range<u64>>::WIRE_SIZE, 16);
+  assert_eq!(vec![range.clone()].encoded_size(), 8 + 16);
+
+  for endian in [ByteEndian::Little, ByteEndian::Big] {
+    assert_eq!(std::ops::Range::from_bytes_exact(&range.to_bytes(endian), endian), Ok(range.clone()));
+    assert_eq!(std::ops::RangeInclusive::from_bytes_exact(&inclusive.to_bytes(endian), endian), Ok(inclusive.clone()));
+  }
+}
\ No newline at end of file
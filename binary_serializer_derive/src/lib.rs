@@ -1,55 +1,746 @@
 use proc_macro::{self, TokenStream};
-use quote::{quote, format_ident};
-use syn::{parse_macro_input, Ident, Fields, DataEnum, Index, DeriveInput, FieldsNamed, FieldsUnnamed};
+use quote::{quote, quote_spanned, format_ident};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Ident, Field, Fields, DataEnum, Index, DeriveInput, FieldsNamed, FieldsUnnamed, Attribute, Generics};
+
+mod attr {
+  use syn::parse::{Parse, ParseStream};
+  use syn::punctuated::Punctuated;
+  use syn::{Expr, Lit, LitStr, Token};
+
+  use crate::*;
+
+  /// Single `name` or `name = value` entry of a `#[binary(...)]` attribute.
+  pub(crate) struct Arg {
+    pub name: Ident,
+    pub value: Option<Expr>,
+  }
+
+  impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+      let name = input.parse()?;
+      let value = if input.peek(Token![=]) {
+        input.parse::<Token![=]>()?;
+        Some(input.parse()?)
+      } else {
+        None
+      };
+
+      Ok(Self { name, value })
+    }
+  }
+
+  impl Arg {
+    pub(crate) fn ident_value(&self) -> syn::Result<&Ident> {
+      match &self.value {
+        Some(Expr::Path(path)) if path.path.get_ident().is_some() => Ok(path.path.get_ident().unwrap()),
+        _ => Err(syn::Error::new_spanned(&self.name, format!("expected `{} = <ident>`", self.name)))
+      }
+    }
+
+    pub(crate) fn str_value(&self) -> syn::Result<LitStr> {
+      match &self.value {
+        Some(Expr::Lit(lit)) => match &lit.lit {
+          Lit::Str(value) => Ok(value.clone()),
+          _ => Err(syn::Error::new_spanned(&self.name, format!("expected `{} = \"...\"`", self.name)))
+        },
+        _ => Err(syn::Error::new_spanned(&self.name, format!("expected `{} = \"...\"`", self.name)))
+      }
+    }
+  }
+
+  pub(crate) fn args(attrs: &[Attribute]) -> syn::Result<Vec<Arg>> {
+    let mut args = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("binary")) {
+      args.extend(attr.parse_args_with(Punctuated::<Arg, Token![,]>::parse_terminated)?);
+    }
+
+    Ok(args)
+  }
+
+  pub(crate) fn unknown(arg: &Arg) -> syn::Error {
+    syn::Error::new_spanned(&arg.name, format!("unknown binary attribute `{}`", arg.name))
+  }
+
+  #[derive(Default)]
+  pub(crate) struct StructAttrs {
+    pub decode_prefix: bool,
+    pub field_offsets: bool,
+    pub layout_hash: Option<LitStr>,
+    /// `#[binary(versioned)]`, fields are written as a length prefixed block after their count,
+    /// so data with fields added to or removed from the end can still be read.
+    /// Fields added later need `default`, decoding fails if any other field is missing.
+    pub versioned: bool,
+    /// `#[binary(transparent)]`, a single field struct encoded exactly as that field on its own.
+    pub transparent: bool,
+  }
+
+  impl StructAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+      let mut result = Self::default();
+
+      for arg in args(attrs)? {
+        match arg.name.to_string().as_str() {
+          "decode_prefix" => result.decode_prefix = true,
+          "field_offsets" => result.field_offsets = true,
+          "layout_hash" => result.layout_hash = Some(arg.str_value()?),
+          "versioned" => result.versioned = true,
+          "transparent" => result.transparent = true,
+          _ => return Err(unknown(&arg))
+        }
+
+        if result.versioned && (result.decode_prefix || result.field_offsets) {
+          return Err(syn::Error::new_spanned(&arg.name, "`versioned` can't be used together with `decode_prefix` or `field_offsets`"));
+        }
+
+        if result.transparent && (result.versioned || result.decode_prefix) {
+          return Err(syn::Error::new_spanned(&arg.name, "`transparent` can't be used together with `versioned` or `decode_prefix`"));
+        }
+      }
+
+      Ok(result)
+    }
+  }
+
+  pub(crate) enum FieldDefault {
+    /// `#[binary(default)]`
+    Trait,
+    /// `#[binary(default = "path::to::fn")]`
+    Fn(syn::Path),
+  }
+
+  #[derive(Default)]
+  pub(crate) struct FieldAttrs {
+    /// `#[binary(none = <value>)]`, an `Option` field written without a tag, `value` stands for `None`.
+    ///
+    /// `Some(value)` can't be written, the encoder fails with `EncoderError::ReservedSentinel`.
+    pub none: Option<Expr>,
+    /// `#[binary(skip)]`, not written at all and `Default` when decoded.
+    pub skip: bool,
+    /// `#[binary(endian = "big" | "little" | "native")]`, the `ByteEndian` variant this field is always written in.
+    pub endian: Option<Ident>,
+    /// `#[binary(default)]` or `#[binary(default = "path::to::fn")]`, still written but only decoded
+    /// if there's input left, so it can be missing from the end of data written before it was added.
+    ///
+    /// Only trailing fields can be `default`, every field after one has to be `default` or `skip` too.
+    /// The default is used only when the decoder is at the very end of its input, so this works for a struct
+    /// that's the last thing decoded. Inside a tuple, `Vec` or another struct with more values after it,
+    /// a missing field is decoded from the bytes of those values instead.
+    /// `versioned` structs don't have that problem, there it marks the fields that may be missing from the written count.
+    ///
+    /// With `skip` it's what the field is decoded as instead of `Default`.
+    pub default: Option<FieldDefault>,
+    /// `#[binary(with = "path::to::module")]`, written by `module::encode(&field, encoder)`
+    /// and read by `module::decode(decoder)`, for types that don't implement the traits themselves.
+    pub with: Option<syn::Path>,
+    /// `#[binary(fixed = N)]`, a `String` or `Vec<u8>` written as exactly `N` bytes with no length prefix,
+    /// cut off or padded with zeros to fit.
+    pub fixed: Option<Expr>,
+  }
+
+  impl FieldAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+      let mut result = Self::default();
+
+      for arg in args(attrs)? {
+        match arg.name.to_string().as_str() {
+          "none" => {
+            let value = arg.value.clone()
+              .ok_or_else(|| syn::Error::new_spanned(&arg.name, "expected `none = <value>`"))?;
+
+            result.none = Some(value);
+          }
+          "skip" => result.skip = true,
+          "default" => {
+            result.default = Some(match &arg.value {
+              Some(_) => FieldDefault::Fn(arg.str_value()?.parse()?),
+              None => FieldDefault::Trait,
+            });
+          }
+          "with" => result.with = Some(arg.str_value()?.parse()?),
+          "fixed" => {
+            let len = arg.value.clone()
+              .ok_or_else(|| syn::Error::new_spanned(&arg.name, "expected `fixed = <len>`"))?;
+
+            result.fixed = Some(len);
+          }
+          "endian" => {
+            let value = arg.str_value()?;
+
+            result.endian = Some(match value.value().as_str() {
+              "big" => format_ident!("Big"),
+              "little" => format_ident!("Little"),
+              "native" => format_ident!("Native"),
+              _ => return Err(syn::Error::new_spanned(value, "expected `\"big\"`, `\"little\"` or `\"native\"`"))
+            });
+          }
+          _ => return Err(unknown(&arg))
+        }
+
+        if result.skip && result.none.is_some() {
+          return Err(syn::Error::new_spanned(&arg.name, "`skip` and `none` can't be used together"));
+        }
+
+        if result.skip && result.endian.is_some() {
+          return Err(syn::Error::new_spanned(&arg.name, "`skip` and `endian` can't be used together"));
+        }
+
+        if result.with.is_some() && (result.skip || result.none.is_some()) {
+          return Err(syn::Error::new_spanned(&arg.name, "`with` can't be used together with `skip` or `none`"));
+        }
+
+        if result.fixed.is_some() && (result.skip || result.none.is_some() || result.with.is_some()) {
+          return Err(syn::Error::new_spanned(&arg.name, "`fixed` can't be used together with `skip`, `none` or `with`"));
+        }
+      }
+
+      Ok(result)
+    }
+
+    /// Expression for the value of a field of type `ty` that isn't decoded.
+    pub(crate) fn default_value(&self, ty: &syn::Type) -> proc_macro2::TokenStream {
+      match &self.default {
+        Some(FieldDefault::Fn(path)) => quote_spanned! { path.span()=> #path() },
+        _ => quote_spanned! { ty.span()=> <#ty as ::core::default::Default>::default() },
+      }
+    }
+  }
+
+  /// `default` fields are told apart from the rest of the input by it ending,
+  /// which only works if nothing that's always decoded comes after them.
+  pub(crate) fn check_defaults_trailing(fields: &Fields) -> syn::Result<()> {
+    let mut defaulted = false;
+
+    for field in fields {
+      let attrs = FieldAttrs::parse(&field.attrs)?;
+
+      if attrs.skip {
+        continue;
+      }
+
+      if attrs.default.is_some() {
+        defaulted = true;
+      } else if defaulted {
+        return Err(syn::Error::new_spanned(field, "fields after a `default` field have to be `default` or `skip` as well"));
+      }
+    }
+
+    Ok(())
+  }
+
+  #[derive(Default)]
+  pub(crate) struct VariantAttrs {
+    pub default_variant: bool,
+  }
+
+  impl VariantAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+      let mut result = Self::default();
+
+      for arg in args(attrs)? {
+        match arg.name.to_string().as_str() {
+          "default_variant" => result.default_variant = true,
+          _ => return Err(unknown(&arg))
+        }
+      }
+
+      Ok(result)
+    }
+  }
+
+  /// The unit variant marked `#[binary(default_variant)]`, if any.
+  pub(crate) fn default_variant(data: &DataEnum) -> syn::Result<Option<&Ident>> {
+    let mut result = None;
+
+    for variant in &data.variants {
+      if !VariantAttrs::parse(&variant.attrs)?.default_variant {
+        continue;
+      }
+
+      if !matches!(variant.fields, Fields::Unit) {
+        return Err(syn::Error::new_spanned(&variant.ident, "`default_variant` has to be a unit variant"));
+      }
+
+      if result.is_some() {
+        return Err(syn::Error::new_spanned(&variant.ident, "only one variant can be `default_variant`"));
+      }
+
+      result = Some(&variant.ident);
+    }
+
+    Ok(result)
+  }
+
+  pub(crate) enum Tag {
+    /// Variant index as `usize` (the default), `u8`, `u16`, `u32` or `u64`.
+    Index(Ident),
+    Hash,
+    /// Variant name as a string, immune to reordering at the cost of a few bytes.
+    Name,
+  }
+
+  impl Tag {
+    /// What the decoded tag is matched as, string literals can't match a `Cow` directly.
+    pub(crate) fn scrutinee(&self) -> proc_macro2::TokenStream {
+      match self {
+        Tag::Name => quote! { &*tag },
+        _ => quote! { tag },
+      }
+    }
+  }
+
+  pub(crate) struct EnumAttrs {
+    pub tag: Tag,
+    pub layout_hash: Option<LitStr>,
+    pub iterative: bool,
+  }
+
+  impl EnumAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+      let mut tag = Tag::Index(format_ident!("usize"));
+      let mut layout_hash = None;
+      let mut iterative = false;
+
+      for arg in args(attrs)? {
+        match arg.name.to_string().as_str() {
+          "tag" => {
+            // `tag = "name"` like every other string valued attribute, or bare as `tag = name`
+            let value = match arg.str_value() {
+              Ok(value) => value.parse::<Ident>()?,
+              Err(_) => arg.ident_value()?.clone(),
+            };
+
+            tag = match value.to_string().as_str() {
+              "hash" => Tag::Hash,
+              "name" => Tag::Name,
+              "u8" | "u16" | "u32" | "u64" | "usize" => Tag::Index(value),
+              _ => return Err(syn::Error::new_spanned(value, "expected `tag = \"hash\"`, `tag = \"name\"` or an unsigned integer type"))
+            };
+          }
+          "layout_hash" => layout_hash = Some(arg.str_value()?),
+          "iterative" => iterative = true,
+          _ => return Err(unknown(&arg))
+        }
+      }
+
+      Ok(Self { tag, layout_hash, iterative })
+    }
+  }
+}
+
+/// `generics` with `bound` added to every type parameter.
+fn bounded(generics: &Generics, bound: proc_macro2::TokenStream) -> Generics {
+  let mut generics = generics.clone();
+  let params = generics.type_params().map(|param| param.ident.clone()).collect::<Vec<_>>();
+  let where_clause = generics.make_where_clause();
+
+  for param in params {
+    where_clause.predicates.push(syn::parse_quote! { #param: #bound });
+  }
+
+  generics
+}
+
+/// 32-bit FNV-1a, used for `#[binary(tag = hash)]`.
+fn fnv1a(value: &str) -> u32 {
+  value.bytes().fold(0x811c9dc5, |hash, byte| (hash ^ byte as u32).wrapping_mul(0x01000193))
+}
+
+/// Names and types of every field, `none` sentinels included since they change the layout too.
+fn fields_layout(fields: &Fields) -> syn::Result<String> {
+  let mut layout = String::new();
+
+  for (idx, field) in fields.iter().enumerate() {
+    let name = field.ident.as_ref().map_or(idx.to_string(), |it| it.to_string());
+    let ty = &field.ty;
+    let attrs = attr::FieldAttrs::parse(&field.attrs)?;
+
+    if attrs.skip {
+      continue;
+    }
+
+    layout.push_str(&format!("{}:{};", name, quote! { #ty }));
+
+    if let Some(none) = attrs.none {
+      layout.push_str(&format!("none={};", quote! { #none }));
+    }
+
+    if let Some(endian) = attrs.endian {
+      layout.push_str(&format!("endian={};", endian));
+    }
+
+    if let Some(with) = attrs.with {
+      layout.push_str(&format!("with={};", quote! { #with }));
+    }
+
+    if let Some(fixed) = attrs.fixed {
+      layout.push_str(&format!("fixed={};", quote! { #fixed }));
+    }
+  }
+
+  Ok(layout)
+}
+
+/// Checks a `#[binary(layout_hash = "...")]` against the FNV-1a hash of `layout`.
+fn check_layout_hash(pinned: &Option<syn::LitStr>, layout: &str) -> syn::Result<()> {
+  let Some(pinned) = pinned else { return Ok(()) };
+  let hash = format!("{:08x}", fnv1a(layout));
+
+  if pinned.value() != hash {
+    return Err(syn::Error::new_spanned(pinned, format!("layout hash is \"{}\", the wire format changed", hash)));
+  }
+
+  Ok(())
+}
+
+/// The one field of a `#[binary(transparent)]` struct and how to access it.
+fn transparent_field(ident: &Ident, fields: &Fields) -> syn::Result<(proc_macro2::TokenStream, Field)> {
+  let mut iter = fields.iter();
+
+  let (Some(field), None) = (iter.next(), iter.next()) else {
+    return Err(syn::Error::new_spanned(ident, "`transparent` structs have to have exactly one field"));
+  };
+
+  // the field is written as is, anything changing that would defeat the point
+  if let Some(arg) = attr::args(&field.attrs)?.first() {
+    return Err(syn::Error::new_spanned(&arg.name, "fields of `transparent` structs can't have binary attributes"));
+  }
+
+  let member = match &field.ident {
+    Some(name) => quote! { #name },
+    None => quote! { 0 },
+  };
+
+  Ok((member, field.clone()))
+}
+
+/// Fields a `#[binary(versioned)]` struct writes, everything but `skip` ones.
+fn versioned_count(fields: &Fields) -> syn::Result<usize> {
+  let mut count = 0;
+
+  for field in fields {
+    if !attr::FieldAttrs::parse(&field.attrs)?.skip {
+      count += 1;
+    }
+  }
+
+  Ok(count)
+}
+
+/// Checks the pinned layout hash of a struct.
+fn check_struct_layout(ident: &Ident, attrs: &attr::StructAttrs, fields: &Fields) -> syn::Result<()> {
+  let versioned = if attrs.versioned { " versioned" } else { "" };
+  check_layout_hash(&attrs.layout_hash, &format!("struct {}{}{{{}}}", ident, versioned, fields_layout(fields)?))
+}
+
+/// Checks the pinned layout hash of an enum, the tag kind and variant order are part of it.
+fn check_enum_layout(ident: &Ident, attrs: &attr::EnumAttrs, data: &DataEnum) -> syn::Result<()> {
+  let tag = match &attrs.tag {
+    attr::Tag::Index(ty) if ty == "usize" => String::from("index"),
+    attr::Tag::Index(ty) => format!("index:{}", ty),
+    attr::Tag::Hash => String::from("hash"),
+    attr::Tag::Name => String::from("name"),
+  };
+  let mut layout = format!("enum {} tag={}{{", ident, tag);
+
+  for variant in &data.variants {
+    layout.push_str(&variant.ident.to_string());
+
+    if let Some((_, expr)) = &variant.discriminant {
+      layout.push_str(&format!("={}", quote! { #expr }));
+    }
+
+    layout.push_str(&format!("{{{}}}", fields_layout(&variant.fields)?));
+  }
+
+  layout.push('}');
+
+  check_layout_hash(&attrs.layout_hash, &layout)
+}
+
+/// Value of an explicit discriminant, only integer literals can be read at expansion time.
+fn discriminant(expr: &syn::Expr) -> syn::Result<u64> {
+  match expr {
+    syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(lit), .. }) => lit.base10_parse(),
+    _ => Err(syn::Error::new_spanned(expr, "discriminant has to be an unsigned integer literal")),
+  }
+}
+
+/// Tag type and per variant tag literal of an enum.
+fn enum_tags(data: &DataEnum, attrs: &attr::EnumAttrs) -> syn::Result<(proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>)> {
+  match &attrs.tag {
+    attr::Tag::Index(ty) => {
+      let max = match ty.to_string().as_str() {
+        "u8" => u8::MAX as u64,
+        "u16" => u16::MAX as u64,
+        "u32" => u32::MAX as u64,
+        _ => u64::MAX,
+      };
+
+      // same as rustc, a variant without an explicit discriminant is the one before it plus one
+      let mut indices = Vec::<(u64, &Ident)>::new();
+      let mut next = Some(0u64);
+
+      for variant in &data.variants {
+        let index = match &variant.discriminant {
+          Some((_, expr)) => discriminant(expr)?,
+          None => next.ok_or_else(|| syn::Error::new_spanned(&variant.ident, "discriminant overflowed"))?,
+        };
+
+        if index > max {
+          return Err(syn::Error::new_spanned(&variant.ident, format!("tag `{}` doesn't fit in `tag = {}`", index, ty)));
+        }
+
+        if let Some((_, other)) = indices.iter().find(|(other, _)| *other == index) {
+          return Err(syn::Error::new_spanned(&variant.ident, format!("tag `{}` of `{}` is already used by `{}`", index, variant.ident, other)));
+        }
+
+        indices.push((index, &variant.ident));
+        next = index.checked_add(1);
+      }
+
+      let tags = indices.iter()
+        .map(|(index, _)| {
+          let index = proc_macro2::Literal::u64_unsuffixed(*index);
+          quote! { #index }
+        })
+        .collect();
+
+      Ok((quote! { #ty }, tags))
+    }
+    attr::Tag::Hash => {
+      let mut hashes = Vec::<(u32, &Ident)>::new();
+
+      for variant in &data.variants {
+        if let Some((_, expr)) = &variant.discriminant {
+          return Err(syn::Error::new_spanned(expr, "`tag = hash` can't be used with explicit discriminants"));
+        }
+
+        let hash = fnv1a(&variant.ident.to_string());
+
+        if let Some((_, other)) = hashes.iter().find(|(other, _)| *other == hash) {
+          return Err(syn::Error::new_spanned(&variant.ident, format!("tag hash of `{}` collides with `{}`", variant.ident, other)));
+        }
+
+        hashes.push((hash, &variant.ident));
+      }
+
+      let tags = hashes.iter()
+        .map(|(hash, _)| {
+          let hash = proc_macro2::Literal::u32_suffixed(*hash);
+          quote! { #hash }
+        })
+        .collect();
+
+      Ok((quote! { u32 }, tags))
+    }
+    attr::Tag::Name => {
+      let mut tags = Vec::new();
+
+      for variant in &data.variants {
+        if let Some((_, expr)) = &variant.discriminant {
+          return Err(syn::Error::new_spanned(expr, "`tag = name` can't be used with explicit discriminants"));
+        }
+
+        let name = variant.ident.to_string();
+        tags.push(quote! { #name });
+      }
+
+      Ok((quote! { ::binary_serializer::__private::Cow<'static, str> }, tags))
+    }
+  }
+}
 
 mod serialize {
   use crate::*;
 
   macro_rules! quote_serializer {
-    ($id:ident: $($tt:tt)*) => {
+    ($id:ident, $generics:ident: $($tt:tt)*) => {{
+      let generics = bounded($generics, quote! { ::binary_serializer::encoder::Serializer });
+      let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
       quote! {
-        impl ::binary_serializer::encoder::Serializer for #$id {
+        impl #impl_generics ::binary_serializer::encoder::Serializer for #$id #ty_generics #where_clause {
           fn encode(&self, encoder: &mut impl ::binary_serializer::encoder::Encoder) {
             $($tt)*
           }
         }
       }
+    }};
+  }
+
+  /// Statement encoding `field`, `value` is a reference to it.
+  fn field(field: &Field, value: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::FieldAttrs::parse(&field.attrs)?;
+
+    let encode = match (&attrs.with, &attrs.none) {
+      _ if attrs.skip => return Ok(quote! {}),
+      _ if attrs.fixed.is_some() => {
+        let len = &attrs.fixed;
+        quote! { encoder.encode_fixed_bytes(::binary_serializer::common::FixedBytes::fixed_bytes(#value, #len), #len) }
+      }
+      (Some(with), _) => quote! { #with::encode(#value, encoder) },
+      (None, Some(none)) => quote! {
+        if let ::core::result::Result::Err(err) = encoder.encode_sentinel_option(#value, #none) {
+          encoder.fail(err)
+        }
+      },
+      (None, None) => quote! { encoder.encode_value(#value) },
     };
+
+    Ok(match attrs.endian {
+      Some(endian) => quote! { encoder.with_endian(::binary_serializer::common::ByteEndian::#endian, |encoder| #encode) },
+      None => encode,
+    })
   }
 
-  pub(crate) fn struct_named(ident: Ident, fields: FieldsNamed) -> proc_macro2::TokenStream {
-    let fields = fields.named.iter()
-      .map(|f| &f.ident)
-      .map(|name| quote! { encoder.encode_value(&self.#name) });
+  pub(crate) fn struct_(ident: Ident, generics: &Generics, attrs: Vec<Attribute>, fields: Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::StructAttrs::parse(&attrs)?;
+    check_struct_layout(&ident, &attrs, &fields)?;
 
-    quote_serializer! {
-      ident: #(#fields);*
+    let mut output = match fields.clone() {
+      _ if attrs.transparent => {
+        let (member, _) = transparent_field(&ident, &fields)?;
+
+        quote_serializer! {
+          ident, generics: ::binary_serializer::encoder::Serializer::encode(&self.#member, encoder)
+        }
+      }
+      _ if attrs.versioned => struct_versioned(ident.clone(), generics, &fields)?,
+      Fields::Named(fields) => struct_named(ident.clone(), generics, fields)?,
+      Fields::Unnamed(fields) => struct_unnamed(ident.clone(), generics, fields)?,
+      Fields::Unit => struct_unit(ident.clone(), generics),
+    };
+
+    if attrs.field_offsets {
+      output.extend(field_offsets(ident, generics, fields)?);
     }
+
+    Ok(output)
+  }
+
+  fn field_offsets(ident: Ident, generics: &Generics, fields: Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = fields.iter()
+      .enumerate()
+      .filter(|(_, f)| !attr::FieldAttrs::parse(&f.attrs).is_ok_and(|it| it.skip))
+      .map(|(idx, f)| {
+        let (name, member) = match &f.ident {
+          Some(name) => (name.to_string(), quote! { #name }),
+          None => {
+            let index = Index::from(idx);
+            (idx.to_string(), quote! { #index })
+          }
+        };
+        let encode = field(f, quote! { &self.#member })?;
+
+        Ok(quote! {
+          let tracker = ::binary_serializer::encoder::ByteTracker::begin(encoder.bytes());
+          #encode;
+          offsets.push((#name, tracker.start, tracker.end(encoder.bytes())));
+        })
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    let generics = bounded(generics, quote! { ::binary_serializer::encoder::Serializer });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+      impl #impl_generics #ident #ty_generics #where_clause {
+        /// `(name, offset, len)` of every field in the encoded bytes of `self`,
+        /// tuple struct fields are named by their index.
+        pub fn field_offsets(&self, endian: ::binary_serializer::common::ByteEndian) -> ::binary_serializer::__private::Vec<(&'static str, usize, usize)> {
+          use ::binary_serializer::encoder::Encoder;
+
+          let mut encoder = ::binary_serializer::encoder::ByteEncoder::new(endian);
+          let mut offsets = ::binary_serializer::__private::Vec::new();
+
+          #(#fields)*
+
+          offsets
+        }
+      }
+    })
+  }
+
+  pub(crate) fn struct_named(ident: Ident, generics: &Generics, fields: FieldsNamed) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = fields.named.iter()
+      .map(|f| {
+        let name = &f.ident;
+        field(f, quote! { &self.#name })
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote_serializer! {
+      ident, generics: #(#fields);*
+    })
   }
 
-  pub(crate) fn struct_unnamed(ident: Ident, fields: FieldsUnnamed) -> proc_macro2::TokenStream {
+  pub(crate) fn struct_unnamed(ident: Ident, generics: &Generics, fields: FieldsUnnamed) -> syn::Result<proc_macro2::TokenStream> {
     let fields = fields.unnamed.iter()
       .enumerate()
-      .map(|(idx, _)| Index::from(idx))
-      .map(|idx| quote! { encoder.encode_value(&self.#idx) });
+      .map(|(idx, f)| {
+        let idx = Index::from(idx);
+        field(f, quote! { &self.#idx })
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
 
-    quote_serializer! {
-      ident: #(#fields);*
-    }
+    Ok(quote_serializer! {
+      ident, generics: #(#fields);*
+    })
   }
 
-  pub(crate) fn struct_unit(ident: Ident) -> proc_macro2::TokenStream {
+  /// The number of fields, then the fields as a length prefixed block.
+  fn struct_versioned(ident: Ident, generics: &Generics, fields: &Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let count = versioned_count(fields)?;
+    let fields = fields.iter()
+      .enumerate()
+      .map(|(idx, f)| {
+        let member = match &f.ident {
+          Some(name) => quote! { #name },
+          None => {
+            let index = Index::from(idx);
+            quote! { #index }
+          }
+        };
+
+        field(f, quote! { &self.#member })
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    let block = match count {
+      0 => quote! { _ },
+      _ => quote! { encoder },
+    };
+
+    Ok(quote_serializer! {
+      ident, generics:
+        use ::binary_serializer::encoder::Encoder as _;
+
+        encoder.encode_usize(#count);
+        encoder.encode_block(|#block| {
+          #(#fields;)*
+        });
+    })
+  }
+
+  pub(crate) fn struct_unit(ident: Ident, generics: &Generics) -> proc_macro2::TokenStream {
     quote_serializer! {
-      ident:
+      ident, generics:
     }
   }
 
-  pub(crate) fn enum_(ident: Ident, data: DataEnum) -> proc_macro2::TokenStream {
+  pub(crate) fn enum_(ident: Ident, generics: &Generics, attrs: Vec<Attribute>, data: DataEnum) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::EnumAttrs::parse(&attrs)?;
+    check_enum_layout(&ident, &attrs, &data)?;
+
+    let (tag_type, tags) = enum_tags(&data, &attrs)?;
+    attr::default_variant(&data)?;
+
     let enum_index = data.variants.iter()
-      .enumerate()
-      .map(|(idx, v)| {
+      .zip(&tags)
+      .map(|(v, index)| {
         let name = &v.ident;
-        let index = Index::from(idx);
         let stmt = match &v.fields {
           Fields::Named(_fields) => quote! { Self::#name { .. } => #index },
           Fields::Unnamed(fields) => {
@@ -65,30 +756,45 @@ mod serialize {
         stmt
       });
 
+    let tag = match attrs.tag {
+      attr::Tag::Name => quote! { ::binary_serializer::__private::Cow::Borrowed(match self { #(#enum_index),* }) },
+      _ => quote! { match self { #(#enum_index),* } },
+    };
+
     let enum_variants = data.variants.iter()
       .map(|v| {
         let name = &v.ident;
         let match_stmt = match &v.fields {
           Fields::Named(fields) => {
-            let fields = fields.named.iter()
+            let names = fields.named.iter()
               .map(|f| &f.ident)
               .collect::<Vec<_>>();
+            let stmts = fields.named.iter()
+              .map(|f| {
+                let name = &f.ident;
+                field(f, quote! { #name })
+              })
+              .collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
-              Self::#name { #(#fields),* } => {
-                #(encoder.encode_value(#fields);)*
+              Self::#name { #(#names),* } => {
+                #(#stmts;)*
               }
             }
           }
           Fields::Unnamed(fields) => {
-            let fields = fields.unnamed.iter()
+            let names = fields.unnamed.iter()
               .enumerate()
               .map(|(idx, _)| format_ident!("_{}", Index::from(idx)))
               .collect::<Vec<_>>();
+            let stmts = fields.unnamed.iter()
+              .zip(&names)
+              .map(|(f, name)| field(f, quote! { #name }))
+              .collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
-              Self::#name(#(#fields),*) => {
-                #(encoder.encode_value(#fields);)*
+              Self::#name(#(#names),*) => {
+                #(#stmts;)*
               }
             }
           }
@@ -99,21 +805,35 @@ mod serialize {
           }
         };
 
-        match_stmt
-      });
+        Ok(match_stmt)
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
 
-    quote_serializer! {
-      ident:
-      let index: usize = match self {
-        #(#enum_index),*
-      };
+    let generics = bounded(generics, quote! { ::binary_serializer::encoder::Serializer });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-      encoder.encode_value(&index);
+    Ok(quote! {
+      impl #impl_generics ::binary_serializer::encoder::Serializer for #ident #ty_generics #where_clause {
+        fn encode(&self, encoder: &mut impl ::binary_serializer::encoder::Encoder) {
+          encoder.encode_enum_tag(self);
+          encoder.encode_enum_payload(self);
+        }
+      }
 
-      match self {
-        #(#enum_variants),*
+      impl #impl_generics ::binary_serializer::encoder::EnumSerializer for #ident #ty_generics #where_clause {
+        type Tag = #tag_type;
+
+        fn tag(&self) -> #tag_type {
+          #tag
+        }
+
+        fn encode_payload(&self, encoder: &mut impl ::binary_serializer::encoder::Encoder) {
+          match self {
+            #(#enum_variants),*
+          }
+        }
       }
-    }
+    })
   }
 }
 
@@ -121,65 +841,271 @@ mod deserialize {
   use crate::*;
 
   macro_rules! quote_deserializer {
-    ($id:ident: $($tt:tt)*) => {
+    ($id:ident, $generics:ident: $($tt:tt)*) => {{
+      let generics = bounded($generics, quote! { ::binary_serializer::decoder::Deserializer });
+      let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
       quote! {
-        impl ::binary_serializer::decoder::Deserializer for #$id {
+        impl #impl_generics ::binary_serializer::decoder::Deserializer for #$id #ty_generics #where_clause {
           fn decode(decoder: &mut impl ::binary_serializer::decoder::Decoder) -> ::binary_serializer::decoder::DecoderResult<Self> {
             $($tt)*
           }
         }
       }
+    }};
+  }
+
+  /// Expression decoding `field`.
+  pub(crate) fn field(field: &Field) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::FieldAttrs::parse(&field.attrs)?;
+    let ty = &field.ty;
+
+    let default = attrs.default_value(ty);
+
+    let decode = match (&attrs.with, &attrs.none) {
+      _ if attrs.skip => return Ok(default),
+      _ if attrs.fixed.is_some() => {
+        let len = &attrs.fixed;
+        quote! {{
+          let start = decoder.position();
+          ::binary_serializer::common::FixedBytes::from_fixed_bytes(decoder.decode_fixed_bytes(#len)?, start)
+        }}
+      }
+      (Some(with), _) => quote! { #with::decode(decoder) },
+      (None, Some(none)) => quote! { decoder.decode_sentinel_option(#none) },
+      (None, None) => quote! { decoder.decode_value() },
+    };
+
+    let decode = match &attrs.endian {
+      Some(endian) => quote! { decoder.with_endian(::binary_serializer::common::ByteEndian::#endian, |decoder| #decode)? },
+      None => quote! { #decode? },
+    };
+
+    // only right for the last value of the input, see `FieldAttrs::default`
+    Ok(match attrs.default {
+      Some(_) => quote! { if decoder.at_end() { #default } else { #decode } },
+      None => decode,
+    })
+  }
+
+  pub(crate) fn struct_(ident: Ident, generics: &Generics, attrs: Vec<Attribute>, fields: Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::StructAttrs::parse(&attrs)?;
+    check_struct_layout(&ident, &attrs, &fields)?;
+
+    // missing fields of versioned structs are known from the count, it's fine for them to be anywhere
+    if !attrs.versioned {
+      attr::check_defaults_trailing(&fields)?;
+    }
+
+    let mut output = match fields.clone() {
+      _ if attrs.transparent => {
+        let (member, field) = transparent_field(&ident, &fields)?;
+        let ty = &field.ty;
+
+        quote_deserializer! {
+          ident, generics: Ok(Self { #member: <#ty as ::binary_serializer::decoder::Deserializer>::decode(decoder)? })
+        }
+      }
+      _ if attrs.versioned => struct_versioned(ident.clone(), generics, &fields)?,
+      Fields::Named(fields) => struct_named(ident.clone(), generics, fields)?,
+      Fields::Unnamed(fields) => struct_unnamed(ident.clone(), generics, fields)?,
+      Fields::Unit => struct_unit(ident.clone(), generics),
     };
+
+    if attrs.decode_prefix {
+      output.extend(decode_prefix(ident, generics, fields)?);
+    }
+
+    Ok(output)
   }
 
-  pub(crate) fn struct_named(ident: Ident, fields: FieldsNamed) -> proc_macro2::TokenStream {
+  fn decode_prefix(ident: Ident, generics: &Generics, fields: Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let values = fields.iter()
+      .map(|f| {
+        let decode = field(f)?;
+
+        Ok(quote! {
+          if decoder.position() - start < budget {
+            #decode
+          } else {
+            ::core::default::Default::default()
+          }
+        })
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    let value = match &fields {
+      Fields::Named(fields) => {
+        let fields = fields.named.iter().map(|f| &f.ident);
+
+        quote! { Self { #(#fields: #values),* } }
+      }
+      Fields::Unnamed(_) => quote! { Self(#(#values),*) },
+      Fields::Unit => quote! { Self }
+    };
+
+    let mut generics = bounded(generics, quote! { ::binary_serializer::decoder::Deserializer });
+    let where_clause = generics.make_where_clause();
+
+    for f in fields.iter() {
+      let ty = &f.ty;
+      where_clause.predicates.push(syn::parse_quote! { #ty: ::core::default::Default });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+      impl #impl_generics #ident #ty_generics #where_clause {
+        /// Decodes fields in order while less than `budget` bytes have been read,
+        /// every field after that is left as its `Default`.
+        ///
+        /// A field that starts inside the budget is always decoded in full.
+        #[allow(unused_variables)]
+        pub fn decode_prefix(decoder: &mut ::binary_serializer::decoder::ByteDecoder, budget: usize) -> ::binary_serializer::decoder::DecoderResult<Self> {
+          use ::binary_serializer::decoder::Decoder;
+
+          let start = decoder.position();
+
+          Ok(#value)
+        }
+      }
+    })
+  }
+
+  /// `default` fields beyond the written count are left as their default, any other field beyond it is an error.
+  /// Written ones beyond the known fields are skipped along with the rest of the block.
+  fn struct_versioned(ident: Ident, generics: &Generics, fields: &Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let mut written = 0usize;
+    let values = fields.iter()
+      .enumerate()
+      .map(|(position, f)| {
+        let attrs = attr::FieldAttrs::parse(&f.attrs)?;
+        let decode = field(f)?;
+
+        if attrs.skip {
+          return Ok(decode);
+        }
+
+        let missing = match &attrs.default {
+          Some(_) => attrs.default_value(&f.ty),
+          None => {
+            let name = f.ident.as_ref().map_or_else(|| position.to_string(), ToString::to_string);
+            let type_name = ident.to_string();
+
+            quote! { return Err(::binary_serializer::decoder::DecoderError::missing_field(#type_name, #name)) }
+          }
+        };
+
+        let idx = written;
+        written += 1;
+
+        Ok(quote! { if #idx < count { #decode } else { #missing } })
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    let value = match fields {
+      Fields::Named(fields) => {
+        let fields = fields.named.iter().map(|f| &f.ident);
+
+        quote! { Self { #(#fields: #values),* } }
+      }
+      Fields::Unnamed(_) => quote! { Self(#(#values),*) },
+      Fields::Unit => quote! { Self }
+    };
+
+    // without any fields to read the block is only skipped
+    let (count, block) = match written {
+      0 => (quote! { _ }, quote! { _ }),
+      _ => (quote! { count }, quote! { decoder }),
+    };
+
+    Ok(quote_deserializer! {
+      ident, generics:
+        use ::binary_serializer::decoder::Decoder as _;
+
+        let #count = decoder.decode_usize()?;
+
+        decoder.decode_block(|#block| Ok(#value))
+    })
+  }
+
+  pub(crate) fn struct_named(ident: Ident, generics: &Generics, fields: FieldsNamed) -> syn::Result<proc_macro2::TokenStream> {
     let fields = fields.named.iter()
-      .map(|f| &f.ident)
-      .map(|name| quote! { #name: decoder.decode_value()? });
+      .map(|f| {
+        let name = &f.ident;
+        let value = field(f)?;
 
-    quote_deserializer! {
-      ident: Ok(Self {
+        Ok(quote! { #name: #value })
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(quote_deserializer! {
+      ident, generics: Ok(Self {
         #(#fields),*
       })
-    }
+    })
   }
 
-  pub(crate) fn struct_unnamed(ident: Ident, fields: FieldsUnnamed) -> proc_macro2::TokenStream {
+  pub(crate) fn struct_unnamed(ident: Ident, generics: &Generics, fields: FieldsUnnamed) -> syn::Result<proc_macro2::TokenStream> {
     let fields = fields.unnamed.iter()
-      .map(|_| quote! { decoder.decode_value()? });
+      .map(field)
+      .collect::<syn::Result<Vec<_>>>()?;
 
-    quote_deserializer! {
-      ident: Ok(Self(#(#fields),*))
-    }
+    Ok(quote_deserializer! {
+      ident, generics: Ok(Self(#(#fields),*))
+    })
   }
 
-  pub(crate) fn struct_unit(ident: Ident) -> proc_macro2::TokenStream {
+  pub(crate) fn struct_unit(ident: Ident, generics: &Generics) -> proc_macro2::TokenStream {
     quote_deserializer! {
-      ident: Ok(Self)
+      ident, generics: Ok(Self)
     }
   }
 
-  pub(crate) fn enum_(ident: Ident, data: DataEnum) -> proc_macro2::TokenStream {
+  pub(crate) fn enum_(ident: Ident, generics: &Generics, attrs: Vec<Attribute>, data: DataEnum) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::EnumAttrs::parse(&attrs)?;
+    check_enum_layout(&ident, &attrs, &data)?;
+
+    for variant in &data.variants {
+      attr::check_defaults_trailing(&variant.fields)?;
+    }
+
+    let (tag_type, tags) = enum_tags(&data, &attrs)?;
+
+    if attrs.iterative {
+      if let Some(param) = generics.type_params().next() {
+        return Err(syn::Error::new_spanned(param, "`iterative` doesn't support type parameters"));
+      }
+    }
+
+    let fallback = match attr::default_variant(&data)? {
+      Some(name) => quote! { _ => Self::#name },
+      None => invalid_variant(&ident, &data, &attrs.tag),
+    };
+
     let enum_variants = data.variants.iter()
-      .enumerate()
-      .map(|(idx, v)| {
+      .zip(&tags)
+      .map(|(v, index)| {
         let name = &v.ident;
-        let index = Index::from(idx);
         let match_stmt = match &v.fields {
           Fields::Named(fields) => {
-            let fields = fields.named.iter()
+            let names = fields.named.iter()
               .map(|f| &f.ident)
               .collect::<Vec<_>>();
+            let values = fields.named.iter()
+              .map(field)
+              .collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
               #index => Self::#name {
-                #(#fields: decoder.decode_value()?),*
+                #(#names: #values),*
               }
             }
           }
           Fields::Unnamed(fields) => {
             let fields = fields.unnamed.iter()
-              .map(|_| quote! { decoder.decode_value()? });
+              .map(field)
+              .collect::<syn::Result<Vec<_>>>()?;
 
             quote! {
               #index => Self::#name(
@@ -194,61 +1120,461 @@ mod deserialize {
           }
         };
 
-        match_stmt
+        Ok(match_stmt)
+      })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    let scrutinee = attrs.tag.scrutinee();
+    let payload = match attrs.iterative {
+      true => iterative::decode_payload(&ident, &data, &tags, &attrs.tag)?,
+      false => quote! {
+        Ok(match #scrutinee {
+          #(#enum_variants,)*
+          #fallback
+        })
+      },
+    };
+
+    let generics = bounded(generics, quote! { ::binary_serializer::decoder::Deserializer });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+      impl #impl_generics ::binary_serializer::decoder::Deserializer for #ident #ty_generics #where_clause {
+        fn decode(decoder: &mut impl ::binary_serializer::decoder::Decoder) -> ::binary_serializer::decoder::DecoderResult<Self> {
+          let tag = decoder.decode_enum_tag::<Self>()?;
+
+          decoder.decode_enum_payload(tag)
+        }
+      }
+
+      impl #impl_generics ::binary_serializer::decoder::EnumDeserializer for #ident #ty_generics #where_clause {
+        type Tag = #tag_type;
+
+        fn decode_payload(tag: #tag_type, decoder: &mut impl ::binary_serializer::decoder::Decoder) -> ::binary_serializer::decoder::DecoderResult<Self> {
+          #payload
+        }
+      }
+    })
+  }
+}
+
+/// Fallback arm of the payload `match`, for tags that don't belong to any variant.
+fn invalid_variant(ident: &Ident, data: &DataEnum, tag: &attr::Tag) -> proc_macro2::TokenStream {
+  let count = data.variants.len();
+
+  match tag {
+    attr::Tag::Name => quote! {
+      tag => return Err(::binary_serializer::decoder::DecoderError::invalid_variant_name(stringify!(#ident), tag))
+    },
+    _ => quote! {
+      tag => return Err(::binary_serializer::decoder::DecoderError::invalid_variant(stringify!(#ident), tag as u64, #count))
+    },
+  }
+}
+
+/// `#[binary(iterative)]`, decodes `Box<Self>` fields with a stack on the heap instead of recursing,
+/// so nesting depth is only bounded by memory.
+///
+/// Every value in progress is a `__Partial` holding the fields decoded so far and the index of the next one.
+/// Reaching a `Box<Self>` field pushes it and starts on the child, finishing a value pops its parent and fills the field in.
+mod iterative {
+  use proc_macro2::{Group, TokenStream, TokenTree};
+
+  use crate::*;
+
+  fn is_boxed_self(ty: &syn::Type, ident: &Ident) -> bool {
+    let syn::Type::Path(path) = ty else { return false };
+    let Some(last) = path.path.segments.last() else { return false };
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else { return false };
+
+    last.ident == "Box" && args.args.len() == 1 && matches!(
+      args.args.first(),
+      Some(syn::GenericArgument::Type(syn::Type::Path(inner))) if inner.path.is_ident("Self") || inner.path.is_ident(ident)
+    )
+  }
+
+  /// `Self` means `__Partial` inside of it, so field types have to name the enum.
+  fn replace_self(tokens: TokenStream, ident: &Ident) -> TokenStream {
+    tokens.into_iter()
+      .map(|token| match token {
+        TokenTree::Ident(it) if it == "Self" => TokenTree::Ident(ident.clone()),
+        TokenTree::Group(group) => {
+          let mut replaced = Group::new(group.delimiter(), replace_self(group.stream(), ident));
+          replaced.set_span(group.span());
+          TokenTree::Group(replaced)
+        }
+        token => token
+      })
+      .collect()
+  }
+
+  pub(crate) fn decode_payload(ident: &Ident, data: &DataEnum, tags: &[TokenStream], tag: &attr::Tag) -> syn::Result<TokenStream> {
+    let mut partials = Vec::new();
+    let mut starts = Vec::new();
+    let mut resumes = Vec::new();
+    let mut fills = Vec::new();
+    let mut finishes = Vec::new();
+
+    for (variant, tag) in data.variants.iter().zip(tags) {
+      let name = &variant.ident;
+      let slots = (0..variant.fields.len()).map(|idx| format_ident!("__f{}", idx)).collect::<Vec<_>>();
+      let types = variant.fields.iter()
+        .map(|f| {
+          let ty = &f.ty;
+          replace_self(quote! { #ty }, ident)
+        });
+
+      partials.push(quote! { #name { #(#slots: Option<#types>,)* } });
+      starts.push(quote! { #tag => __Partial::#name { #(#slots: None,)* } });
+
+      let mut steps = Vec::new();
+      let mut boxed = Vec::new();
+
+      for (idx, (f, slot)) in variant.fields.iter().zip(&slots).enumerate() {
+        if is_boxed_self(&f.ty, ident) && !attr::FieldAttrs::parse(&f.attrs)?.skip {
+          steps.push(quote! { #idx => break true });
+          boxed.push(quote! { #idx => *#slot = Some(::binary_serializer::__private::Box::new(value)) });
+        } else {
+          let decode = deserialize::field(f)?;
+          steps.push(quote! { #idx => *#slot = Some(#decode) });
+        }
+      }
+
+      resumes.push(quote! {
+        (__Partial::#name { #(#slots,)* }, step) => loop {
+          match *step {
+            #(#steps,)*
+            _ => break false
+          }
+
+          *step += 1;
+        }
+      });
+
+      fills.push(quote! {
+        (__Partial::#name { #(#slots,)* }, step) => match *step {
+          #(#boxed,)*
+          _ => unreachable!()
+        }
       });
 
+      let unwrapped = slots.iter().map(|slot| quote! { #slot.unwrap() });
 
-    quote_deserializer! {
-      ident:
-      let index: usize = decoder.decode_value()?;
+      finishes.push(match &variant.fields {
+        Fields::Named(fields) => {
+          let names = fields.named.iter().map(|f| &f.ident);
+
+          quote! { __Partial::#name { #(#slots,)* } => Self::#name { #(#names: #unwrapped),* } }
+        }
+        Fields::Unnamed(_) => quote! { __Partial::#name { #(#slots,)* } => Self::#name(#(#unwrapped),*) },
+        Fields::Unit => quote! { __Partial::#name {} => Self::#name },
+      });
+    }
+
+    let fallback = match attr::default_variant(data)? {
+      Some(name) => quote! { _ => __Partial::#name {} },
+      None => invalid_variant(ident, data, tag),
+    };
+    let scrutinee = tag.scrutinee();
+
+    Ok(quote! {
+      #[allow(non_camel_case_types)]
+      enum __Partial {
+        #(#partials,)*
+      }
+
+      let start = |tag: <Self as ::binary_serializer::decoder::EnumDeserializer>::Tag| -> ::binary_serializer::decoder::DecoderResult<(__Partial, usize)> {
+        Ok((match #scrutinee {
+          #(#starts,)*
+          #fallback
+        }, 0))
+      };
+
+      let mut stack = ::binary_serializer::__private::Vec::new();
+      let mut current = start(tag)?;
+
+      loop {
+        #[allow(unused_variables, unreachable_code)]
+        let needs_child = match &mut current {
+          #(#resumes,)*
+        };
+
+        if needs_child {
+          let tag = decoder.decode_enum_tag::<Self>()?;
+
+          stack.push(current);
+          current = start(tag)?;
+          continue;
+        }
+
+        let value = match current.0 {
+          #(#finishes,)*
+        };
+
+        current = match stack.pop() {
+          Some(mut parent) => {
+            #[allow(unused_variables)]
+            match &mut parent {
+              #(#fills,)*
+            }
 
-      Ok(match index {
-        #(#enum_variants,)*
-        _ => return Err(::binary_serializer::decoder::DecoderError::custom("Invalid Enum"))
+            parent.1 += 1;
+            parent
+          }
+          None => return Ok(value)
+        };
+      }
+    })
+  }
+}
+
+mod size_hint {
+  use crate::*;
+
+  /// Expression hinting the encoded size of `field`, `value` is a reference to it.
+  fn field(field: &Field, value: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::FieldAttrs::parse(&field.attrs)?;
+
+    Ok(match attrs.none {
+      _ if attrs.skip => quote! { 0 },
+      // nothing to go on for foreign types
+      _ if attrs.with.is_some() => quote! { 0 },
+      _ if attrs.fixed.is_some() => {
+        let len = &attrs.fixed;
+        quote! { #len }
+      }
+      // the sentinel written for `None` is the same type as the value, a lower bound of `0` is close enough
+      Some(_) => quote! { Option::as_ref(#value).map_or(0, ::binary_serializer::size_hint::SizeHint::encoded_size) },
+      None => quote! { ::binary_serializer::size_hint::SizeHint::encoded_size(#value) },
+    })
+  }
+
+  /// Pattern binding every field of `fields` by reference and the sum of their hints.
+  fn bind(fields: &Fields) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let names = fields.iter()
+      .enumerate()
+      .map(|(idx, f)| f.ident.clone().unwrap_or_else(|| format_ident!("_{}", Index::from(idx))))
+      .collect::<Vec<_>>();
+    let hints = fields.iter()
+      .zip(&names)
+      .map(|(f, name)| field(f, quote! { #name }))
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    let pattern = match fields {
+      Fields::Named(_) => quote! { { #(#names),* } },
+      Fields::Unnamed(_) => quote! { (#(#names),*) },
+      Fields::Unit => quote! {},
+    };
+
+    Ok((pattern, quote! { 0 #(+ #hints)* }))
+  }
+
+  pub(crate) fn struct_(ident: Ident, generics: &Generics, attrs: Vec<Attribute>, fields: Fields) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::StructAttrs::parse(&attrs)?;
+    let (pattern, mut sum) = bind(&fields)?;
+
+    // the field count and the length of the block, fixed width like every other length a hint assumes
+    if attrs.versioned {
+      sum = quote! { 2 * <usize as ::binary_serializer::common::FixedSize>::WIRE_SIZE + #sum };
+    }
+
+    let generics = bounded(generics, quote! { ::binary_serializer::size_hint::SizeHint });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+      impl #impl_generics ::binary_serializer::size_hint::SizeHint for #ident #ty_generics #where_clause {
+        #[allow(unused_variables)]
+        fn encoded_size(&self) -> usize {
+          let Self #pattern = self;
+
+          #sum
+        }
+      }
+    })
+  }
+
+  pub(crate) fn enum_(ident: Ident, generics: &Generics, attrs: Vec<Attribute>, data: DataEnum) -> syn::Result<proc_macro2::TokenStream> {
+    let attrs = attr::EnumAttrs::parse(&attrs)?;
+    let (tag_type, tags) = enum_tags(&data, &attrs)?;
+
+    let variants = data.variants.iter()
+      .zip(&tags)
+      .map(|(v, tag)| {
+        let name = &v.ident;
+        let (pattern, sum) = bind(&v.fields)?;
+
+        Ok(match attrs.tag {
+          attr::Tag::Name => quote! { Self::#name #pattern => ::binary_serializer::size_hint::SizeHint::encoded_size(#tag) + #sum },
+          _ => quote! { Self::#name #pattern => #sum },
+        })
       })
+      .collect::<syn::Result<Vec<_>>>()?;
+
+    // names differ in length, they're counted per variant
+    let tag_size = match attrs.tag {
+      attr::Tag::Name => quote! { 0 },
+      _ => quote! { <#tag_type as ::binary_serializer::common::FixedSize>::WIRE_SIZE },
+    };
+
+    let generics = bounded(generics, quote! { ::binary_serializer::size_hint::SizeHint });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+      impl #impl_generics ::binary_serializer::size_hint::SizeHint for #ident #ty_generics #where_clause {
+        #[allow(unused_variables)]
+        fn encoded_size(&self) -> usize {
+          #tag_size + match self {
+            #(#variants,)*
+          }
+        }
+      }
+    })
+  }
+}
+
+mod deserialize_borrowed {
+  use crate::*;
+
+  pub(crate) fn struct_(ident: Ident, generics: Generics, fields: Fields) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some(param) = generics.type_params().next() {
+      return Err(syn::Error::new_spanned(param, "`DeserializerBorrowed` doesn't support type parameters"));
     }
+
+    let lifetime = generics.lifetimes().next().map(|it| it.lifetime.clone());
+    let (impl_lifetime, ty) = match &lifetime {
+      Some(lifetime) => (lifetime.clone(), quote! { #ident<#lifetime> }),
+      None => (syn::Lifetime::new("'de", proc_macro2::Span::call_site()), quote! { #ident }),
+    };
+
+    let field = quote! { ::binary_serializer::decoder::BorrowedDeserializer::decode_borrowed(decoder)? };
+    let value = match fields {
+      Fields::Named(fields) => {
+        let fields = fields.named.iter().map(|f| &f.ident);
+
+        quote! { Self { #(#fields: #field),* } }
+      }
+      Fields::Unnamed(fields) => {
+        let fields = fields.unnamed.iter().map(|_| &field);
+
+        quote! { Self(#(#fields),*) }
+      }
+      Fields::Unit => quote! { Self }
+    };
+
+    Ok(quote! {
+      impl<#impl_lifetime> ::binary_serializer::decoder::BorrowedDeserializer<#impl_lifetime> for #ty {
+        fn decode_borrowed(decoder: &mut ::binary_serializer::decoder::ByteDecoder<#impl_lifetime>) -> ::binary_serializer::decoder::DecoderResult<Self> {
+          Ok(#value)
+        }
+      }
+    })
   }
 }
 
-fn unimpl(_typ: &str) -> proc_macro2::TokenStream {
-  quote! {
-    compile_error!("Unimplemented: {}", _typ);
+/// Nothing in a union says which of its fields is the active one, so there's no safe way to pick what to encode.
+fn union_unsupported(data: &syn::DataUnion) -> proc_macro2::TokenStream {
+  syn::Error::new_spanned(
+    data.union_token,
+    "unions can't be derived since the active field isn't known, use an enum or implement the traits by hand",
+  ).to_compile_error()
+}
+
+/// First decoded field that holds a reference or one of the lifetimes of `generics`, lifetimes only used
+/// for a `PhantomData` are fine since nothing is borrowed through them.
+fn borrowing_field<'a>(data: &'a syn::Data, generics: &Generics) -> Option<&'a Field> {
+  let lifetimes = generics.lifetimes().map(|param| &param.lifetime).collect::<Vec<_>>();
+
+  if lifetimes.is_empty() {
+    return None;
   }
+
+  let fields: Vec<&Field> = match data {
+    syn::Data::Struct(data) => data.fields.iter().collect(),
+    syn::Data::Enum(data) => data.variants.iter().flat_map(|variant| &variant.fields).collect(),
+    syn::Data::Union(_) => Vec::new(),
+  };
+
+  fields.into_iter()
+    .filter(|field| !attr::FieldAttrs::parse(&field.attrs).is_ok_and(|attrs| attrs.skip))
+    .find(|field| borrows(&field.ty, &lifetimes))
 }
 
-#[proc_macro_derive(Serializer)]
+/// Anything that isn't plainly made of paths, tuples, arrays and slices is assumed to borrow.
+fn borrows(ty: &syn::Type, lifetimes: &[&syn::Lifetime]) -> bool {
+  match ty {
+    syn::Type::Path(path) if path.qself.is_none() => path.path.segments.iter()
+      .filter(|segment| segment.ident != "PhantomData")
+      .any(|segment| match &segment.arguments {
+        syn::PathArguments::None => false,
+        syn::PathArguments::AngleBracketed(args) => args.args.iter().any(|arg| match arg {
+          syn::GenericArgument::Lifetime(lifetime) => lifetimes.contains(&lifetime),
+          syn::GenericArgument::Type(ty) => borrows(ty, lifetimes),
+          syn::GenericArgument::Const(_) => false,
+          _ => true,
+        }),
+        syn::PathArguments::Parenthesized(_) => true,
+      }),
+    syn::Type::Array(array) => borrows(&array.elem, lifetimes),
+    syn::Type::Slice(slice) => borrows(&slice.elem, lifetimes),
+    syn::Type::Tuple(tuple) => tuple.elems.iter().any(|ty| borrows(ty, lifetimes)),
+    syn::Type::Paren(paren) => borrows(&paren.elem, lifetimes),
+    syn::Type::Group(group) => borrows(&group.elem, lifetimes),
+    _ => true,
+  }
+}
+
+#[proc_macro_derive(Serializer, attributes(binary))]
 pub fn serialize(input: TokenStream) -> TokenStream {
-  let DeriveInput { ident, data, .. } = parse_macro_input!(input);
+  let DeriveInput { ident, generics, attrs, data, .. } = parse_macro_input!(input);
 
   let output = match data {
-    syn::Data::Struct(s) => match s.fields {
-      syn::Fields::Named(fields) => serialize::struct_named(ident, fields),
-      syn::Fields::Unnamed(fields) => serialize::struct_unnamed(ident, fields),
-      syn::Fields::Unit => serialize::struct_unit(ident),
-    },
-    syn::Data::Enum(data) => serialize::enum_(ident, data),
-    syn::Data::Union(_) => {
-      unimpl("Union?")
-    }
+    syn::Data::Struct(s) => serialize::struct_(ident, &generics, attrs, s.fields).unwrap_or_else(|err| err.to_compile_error()),
+    syn::Data::Enum(data) => serialize::enum_(ident, &generics, attrs, data).unwrap_or_else(|err| err.to_compile_error()),
+    syn::Data::Union(data) => union_unsupported(&data),
   };
 
   output.into()
 }
 
-#[proc_macro_derive(Deserializer)]
+#[proc_macro_derive(Deserializer, attributes(binary))]
 pub fn deserialize(input: TokenStream) -> TokenStream {
-  let DeriveInput { ident, data, .. } = parse_macro_input!(input);
+  let DeriveInput { ident, generics, attrs, data, .. } = parse_macro_input!(input);
+
+  // decoded values own their data, borrowing from the input is what `DeserializerBorrowed` is for
+  if let Some(field) = borrowing_field(&data, &generics) {
+    let msg = "`Deserializer` can't be derived for types that borrow, derive `Serializer` alone or use `DeserializerBorrowed`";
+    return syn::Error::new_spanned(field, msg).to_compile_error().into();
+  }
 
   let output = match data {
-    syn::Data::Struct(s) => match s.fields {
-      syn::Fields::Named(fields) => deserialize::struct_named(ident, fields),
-      syn::Fields::Unnamed(fields) => deserialize::struct_unnamed(ident, fields),
-      syn::Fields::Unit => deserialize::struct_unit(ident),
-    },
-    syn::Data::Enum(data) => deserialize::enum_(ident, data),
-    syn::Data::Union(_) => {
-      unimpl("Union?")
-    }
+    syn::Data::Struct(s) => deserialize::struct_(ident, &generics, attrs, s.fields).unwrap_or_else(|err| err.to_compile_error()),
+    syn::Data::Enum(data) => deserialize::enum_(ident, &generics, attrs, data).unwrap_or_else(|err| err.to_compile_error()),
+    syn::Data::Union(data) => union_unsupported(&data),
+  };
+
+  output.into()
+}
+
+#[proc_macro_derive(DeserializerBorrowed)]
+pub fn deserialize_borrowed(input: TokenStream) -> TokenStream {
+  let DeriveInput { ident, generics, data, .. } = parse_macro_input!(input);
+
+  let output = match data {
+    syn::Data::Struct(s) => deserialize_borrowed::struct_(ident, generics, s.fields),
+    _ => Err(syn::Error::new_spanned(ident, "`DeserializerBorrowed` can only be derived for structs")),
+  };
+
+  output.unwrap_or_else(|err| err.to_compile_error()).into()
+}
+
+#[proc_macro_derive(SizeHint, attributes(binary))]
+pub fn size_hint(input: TokenStream) -> TokenStream {
+  let DeriveInput { ident, generics, attrs, data, .. } = parse_macro_input!(input);
+
+  let output = match data {
+    syn::Data::Struct(s) => size_hint::struct_(ident, &generics, attrs, s.fields).unwrap_or_else(|err| err.to_compile_error()),
+    syn::Data::Enum(data) => size_hint::enum_(ident, &generics, attrs, data).unwrap_or_else(|err| err.to_compile_error()),
+    syn::Data::Union(data) => union_unsupported(&data),
   };
 
   output.into()